@@ -0,0 +1,351 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MutationParameters {
+    pub chance: f32,
+    pub coeff: f32,
+}
+
+// How the run has been going so far, handed to `MutationMethod::adapt`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MutationFeedback {
+    // Generations evolved before this one
+    pub generation: usize,
+    // Share of the population that beat the average fitness of its parents;
+    // `None` when the population wasn't bred by this GA (e.g. generation 0)
+    pub success_rate: Option<f32>,
+    // Generations since the best fitness last improved
+    pub stagnation: usize,
+}
+
+// Gaussian mutation whose chance and coefficient follow `MutationControl`.
+// Both are adjusted before every generation is bred and get reported in
+// `Statistics::mutation`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdaptiveGaussianMutation {
+    initial: MutationParameters,
+    mutation: GaussianMutation,
+    control: MutationControl,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MutationControl {
+    // Rechenberg's 1/5 success rule: when more than a fifth of the children
+    // beat their parents the coefficient grows (divided by `factor`),
+    // when fewer do it shrinks (multiplied by `factor`, usually ~0.82);
+    // it never grows past `max_coeff`
+    OneFifthRule { factor: f32, max_coeff: f32 },
+    // Both values are multiplied by `factor` while the best fitness hasn't
+    // improved for `after` generations, and drop back once it does
+    StagnationBoost { after: usize, factor: f32 },
+    // Both values are multiplied by `rate` every generation, down to `min`
+    Decay { rate: f32, min: MutationParameters },
+}
+
+// Feedback the GA keeps between generations
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Adaptation {
+    generation: usize,
+    #[serde(with = "non_finite::option")]
+    best_fitness: Option<f32>,
+    stagnation: usize,
+    // Average fitness of the parents of every child bred last time
+    #[serde(with = "non_finite::vec")]
+    parent_fitness: Vec<f32>,
+}
+// ---------------------------------------------------------------
+
+
+// ------------------ Adaptive Mutation Implementation -----------
+impl AdaptiveGaussianMutation {
+    pub fn new(chance: f32, coeff: f32, control: MutationControl) -> Self {
+        match control {
+            MutationControl::OneFifthRule { factor, max_coeff } => {
+                assert!(factor > 0.0 && factor < 1.0);
+                assert!(max_coeff >= coeff);
+            }
+
+            MutationControl::StagnationBoost { factor, .. } => assert!(factor > 0.0),
+
+            MutationControl::Decay { rate, min } => {
+                assert!(rate > 0.0 && rate <= 1.0);
+                assert!((0.0..=1.0).contains(&min.chance));
+                assert!(min.coeff >= 0.0);
+            }
+        }
+
+        Self {
+            initial: MutationParameters { chance, coeff },
+            mutation: GaussianMutation::new(chance, coeff),
+            control,
+        }
+    }
+
+    pub fn control(&self) -> MutationControl {
+        self.control
+    }
+}
+
+impl MutationMethod for AdaptiveGaussianMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        self.mutation.mutate(rng, child);
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        let initial = self.initial;
+        let current = &mut self.mutation;
+
+        match self.control {
+            MutationControl::OneFifthRule { factor, max_coeff } => match feedback.success_rate {
+                Some(rate) if rate > 0.2 => current.coeff = (current.coeff / factor).min(max_coeff),
+                Some(rate) if rate < 0.2 => current.coeff *= factor,
+                _ => {}
+            },
+
+            MutationControl::StagnationBoost { after, factor } => {
+                let boost = if feedback.stagnation >= after { factor } else { 1.0 };
+
+                current.chance = (initial.chance * boost).min(1.0);
+                current.coeff = initial.coeff * boost;
+            }
+
+            MutationControl::Decay { rate, min } => {
+                let decay = rate.powi(feedback.generation as i32);
+
+                current.chance = (initial.chance * decay).max(min.chance);
+                current.coeff = (initial.coeff * decay).max(min.coeff);
+            }
+        }
+
+        // `GaussianMutation` panics on a chance outside of <0, 1>
+        current.chance = current.chance.clamp(0.0, 1.0);
+    }
+
+    fn parameters(&self) -> Option<MutationParameters> {
+        self.mutation.parameters()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Adaptation Implementation ---------------
+impl Adaptation {
+    // Feedback for the population that's about to be bred
+    pub(crate) fn feedback<I, G>(&mut self, population: &[I]) -> MutationFeedback
+    where
+        I: Individual<G>,
+    {
+        let best = population
+            .iter()
+            .map(I::fitness)
+            .fold(f32::NEG_INFINITY, f32::max);
+
+        match self.best_fitness {
+            Some(best_fitness) if best <= best_fitness => self.stagnation += 1,
+            _ => {
+                self.best_fitness = Some(best);
+                self.stagnation = 0;
+            }
+        }
+
+        let success_rate = self.successes(population).map(|successes| {
+            let successes = successes.iter().filter(|&&success| success).count();
+            successes as f32 / population.len() as f32
+        });
+
+        let feedback = MutationFeedback {
+            generation: self.generation,
+            success_rate,
+            stagnation: self.stagnation,
+        };
+
+        self.generation += 1;
+
+        feedback
+    }
+
+    // Whether every child beat the average fitness of its parents; `None`
+    // when the population wasn't bred by this GA
+    pub(crate) fn successes<I, G>(&self, population: &[I]) -> Option<Vec<bool>>
+    where
+        I: Individual<G>,
+    {
+        // Children keep their order, so a population of a different size
+        // can't have been bred by us
+        (self.parent_fitness.len() == population.len()).then(|| {
+            population
+                .iter()
+                .zip(&self.parent_fitness)
+                .map(|(child, parents)| child.fitness() > *parents)
+                .collect()
+        })
+    }
+
+    // Whatever was learned about the population is gone after a restart
+    pub(crate) fn restarted(&mut self) {
+        self.best_fitness = None;
+        self.stagnation = 0;
+        self.parent_fitness.clear();
+    }
+
+    pub(crate) fn bred<I, G>(&mut self, population: &[I], parents: &[[usize; 2]])
+    where
+        I: Individual<G>,
+    {
+        self.parent_fitness = parents
+            .iter()
+            .map(|[a, b]| (population[*a].fitness() + population[*b].fitness()) / 2.0)
+            .collect();
+    }
+
+    // Steady-state counterpart of `bred`: a round of one-by-one births
+    // starts, whoever doesn't get replaced during it counts as no success
+    pub(crate) fn round(&mut self, size: usize) {
+        self.parent_fitness = vec![f32::NAN; size];
+    }
+
+    // A child of `parents` took the place of `victim`, `None` meaning it
+    // isn't what the operators bred (e.g. it got filtered out)
+    pub(crate) fn born<I, G>(&mut self, population: &[I], victim: usize, parents: Option<[usize; 2]>)
+    where
+        I: Individual<G>,
+    {
+        if let Some(fitness) = self.parent_fitness.get_mut(victim) {
+            *fitness = parents.map_or(f32::NAN, |[a, b]| {
+                (population[a].fitness() + population[b].fitness()) / 2.0
+            });
+        }
+    }
+
+    // Individuals of the current round that weren't bred by the operators
+    pub(crate) fn unborn(&self) -> Vec<usize> {
+        (0..self.parent_fitness.len())
+            .filter(|&index| self.parent_fitness[index].is_nan())
+            .collect()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn feedback(generation: usize, success_rate: Option<f32>, stagnation: usize) -> MutationFeedback {
+        MutationFeedback { generation, success_rate, stagnation }
+    }
+
+    fn adapted(control: MutationControl, feedback: &[MutationFeedback]) -> MutationParameters {
+        let mut mutation = AdaptiveGaussianMutation::new(0.1, 1.0, control);
+
+        for feedback in feedback {
+            mutation.adapt(feedback);
+        }
+
+        mutation.parameters().unwrap()
+    }
+
+    #[test]
+    fn one_fifth_rule() {
+        let control = MutationControl::OneFifthRule { factor: 0.5, max_coeff: 5.0 };
+
+        let grown = adapted(control, &[feedback(1, Some(0.5), 0), feedback(2, Some(0.5), 0)]);
+        approx::assert_relative_eq!(grown.coeff, 4.0);
+
+        let capped = adapted(control, &vec![feedback(1, Some(0.5), 0); 3]);
+        approx::assert_relative_eq!(capped.coeff, 5.0);
+
+        let shrunk = adapted(control, &[feedback(1, Some(0.1), 0), feedback(2, None, 0)]);
+        approx::assert_relative_eq!(shrunk.coeff, 0.5);
+        approx::assert_relative_eq!(shrunk.chance, 0.1);
+    }
+
+    #[test]
+    fn stagnation_boost() {
+        let control = MutationControl::StagnationBoost { after: 3, factor: 4.0 };
+
+        let boosted = adapted(control, &[feedback(5, None, 3)]);
+        assert_eq!(boosted, MutationParameters { chance: 0.4, coeff: 4.0 });
+
+        let calmed = adapted(control, &[feedback(5, None, 3), feedback(6, None, 0)]);
+        assert_eq!(calmed, MutationParameters { chance: 0.1, coeff: 1.0 });
+    }
+
+    #[test]
+    fn decay() {
+        let min = MutationParameters { chance: 0.05, coeff: 0.1 };
+        let control = MutationControl::Decay { rate: 0.5, min };
+
+        let decayed = adapted(control, &[feedback(2, None, 0)]);
+        assert_eq!(decayed, MutationParameters { chance: 0.05, coeff: 0.25 });
+    }
+
+    #[test]
+    fn ga_feeds_back_and_reports_parameters() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            AdaptiveGaussianMutation::new(
+                0.5,
+                1.0,
+                MutationControl::Decay { rate: 0.5, min: MutationParameters { chance: 0.0, coeff: 0.0 } },
+            ),
+        );
+
+        let mut population: Vec<TestIndividual> = (0..4)
+            .map(|i| TestIndividual::create(vec![i as f32; 2].into_iter().collect()))
+            .collect();
+
+        let mut coeffs = Vec::new();
+
+        for _ in 0..3 {
+            let stats;
+            (population, stats) = Optimizer::evolve(&mut ga, &mut rng, &population);
+            coeffs.push(stats.mutation.unwrap().coeff);
+        }
+
+        approx::assert_relative_eq!(coeffs.as_slice(), [1.0, 0.5, 0.25].as_ref());
+    }
+
+    #[test]
+    fn success_rate_compares_children_with_their_parents() {
+        let mut adaptation = Adaptation::default();
+        let parents: Vec<TestIndividual> = [1.0, 3.0].into_iter().map(TestIndividual::new).collect();
+
+        assert_eq!(adaptation.feedback(&parents).success_rate, None);
+
+        adaptation.bred(&parents, &[[0, 1], [0, 0]]);
+
+        let children: Vec<TestIndividual> = [1.5, 1.5].into_iter().map(TestIndividual::new).collect();
+        let feedback = adaptation.feedback(&children);
+
+        assert_eq!(feedback.success_rate, Some(0.5));
+        assert_eq!(feedback.stagnation, 1);
+        assert_eq!(feedback.generation, 1);
+    }
+
+    #[test]
+    fn keeps_non_finite_fitness_in_json() {
+        let adaptation = Adaptation {
+            best_fitness: Some(f32::NEG_INFINITY),
+            parent_fitness: vec![f32::INFINITY, 0.5],
+            ..Adaptation::default()
+        };
+
+        let json = serde_json::to_string(&adaptation).unwrap();
+        let restored: Adaptation = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.best_fitness, adaptation.best_fitness);
+        assert_eq!(restored.parent_fitness, adaptation.parent_fitness);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,504 @@
+use crate::*;
+
+use serde::{
+    Deserializer,
+    Serializer,
+    de::DeserializeOwned,
+};
+use std::{
+    error::Error,
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+// ------------------------- Error -------------------------------
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Binary(String),
+    // Binary data that doesn't start with the checkpoint header
+    NotACheckpoint,
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    // There are no chromosomes to save or to start from
+    EmptyPopulation,
+    // Saved chromosomes don't fit the individuals they're meant for
+    GeneCount {
+        found: usize,
+        expected: usize,
+    },
+    // Initializer whose parameters can't produce any chromosomes, e.g. an
+    // empty range
+    InvalidInitializer(String),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckpointError::Io(err) => write!(f, "Couldn't access the checkpoint: {err}"),
+            CheckpointError::Json(err) => write!(f, "Invalid JSON checkpoint: {err}"),
+            CheckpointError::Binary(err) => write!(f, "Invalid binary checkpoint: {err}"),
+            CheckpointError::NotACheckpoint => write!(f, "Not a checkpoint"),
+            CheckpointError::UnsupportedVersion { found, supported } => {
+                write!(f, "Checkpoint version {found} isn't supported (expected {supported})")
+            }
+            CheckpointError::EmptyPopulation => write!(f, "Got no chromosomes"),
+            CheckpointError::GeneCount { found, expected } => {
+                write!(f, "Chromosomes have {found} genes (expected {expected})")
+            }
+            CheckpointError::InvalidInitializer(reason) => write!(f, "Invalid initializer: {reason}"),
+        }
+    }
+}
+
+impl Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        CheckpointError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CheckpointError {
+    fn from(err: serde_json::Error) -> Self {
+        CheckpointError::Json(err)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ----------------------- Definitions ---------------------------
+// Bumped whenever the layout of `Checkpoint` changes
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+// Binary checkpoints start with these bytes, followed by the version (u32,
+// little endian) and the bincode-encoded checkpoint
+const CHECKPOINT_MAGIC: &[u8; 4] = b"GACP";
+
+// Everything needed to resume a run exactly where it stopped: the evaluated
+// population, the optimizer's parameters (e.g. a whole `GeneticAlgorithm`,
+// adaptation state included) and the RNG state.
+//
+// Operators picked at runtime (`DynGeneticAlgorithm`) can't be serialized,
+// store their `OperatorConfig`s as the parameters instead.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Checkpoint<P, G = f32> {
+    version: u32,
+    pub generation: usize,
+    pub population: Vec<EvaluatedChromosome<G>>,
+    pub parameters: P,
+    pub rng: ChaCha8Rng,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvaluatedChromosome<G = f32> {
+    pub chromosome: Chromosome<G>,
+    #[serde(with = "non_finite")]
+    pub fitness: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckpointFormat {
+    Binary,
+    Json,
+}
+
+// Read before the rest of a JSON checkpoint, so that a newer checkpoint is
+// reported as such instead of failing on a missing field
+#[derive(Deserialize)]
+struct CheckpointHeader {
+    version: u32,
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Checkpoint Implementation ---------------
+impl<P, G> Checkpoint<P, G> {
+    pub fn new<I>(generation: usize, population: &[I], parameters: P, rng: &ChaCha8Rng) -> Self
+    where
+        I: Individual<G>,
+        G: Clone,
+    {
+        Self {
+            version: CHECKPOINT_VERSION,
+            generation,
+            population: population
+                .iter()
+                .map(|individual| EvaluatedChromosome {
+                    chromosome: individual.chromosome().clone(),
+                    fitness: individual.fitness(),
+                })
+                .collect(),
+            parameters,
+            rng: rng.clone(),
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    // Recreates the population; fitness is computed again by the individuals
+    pub fn individuals<I>(&self) -> Vec<I>
+    where
+        I: Individual<G>,
+        G: Clone,
+    {
+        self.population
+            .iter()
+            .map(|entry| I::create(entry.chromosome.clone()))
+            .collect()
+    }
+}
+
+impl<P, G> Checkpoint<P, G>
+where
+    P: Serialize,
+    G: Serialize,
+{
+    pub fn to_json(&self) -> Result<String, CheckpointError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CheckpointError> {
+        let mut bytes = CHECKPOINT_MAGIC.to_vec();
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+
+        bincode::serde::encode_into_std_write(self, &mut bytes, bincode::config::standard())
+            .map_err(|err| CheckpointError::Binary(err.to_string()))?;
+
+        Ok(bytes)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>, format: CheckpointFormat) -> Result<(), CheckpointError> {
+        let bytes = match format {
+            CheckpointFormat::Binary => self.to_bytes()?,
+            CheckpointFormat::Json => self.to_json()?.into_bytes(),
+        };
+
+        write_atomically(path.as_ref(), &bytes)
+    }
+}
+
+impl<P, G> Checkpoint<P, G>
+where
+    P: DeserializeOwned,
+    G: DeserializeOwned,
+{
+    pub fn from_json(json: &str) -> Result<Self, CheckpointError> {
+        let header: CheckpointHeader = serde_json::from_str(json)?;
+        check_version(header.version)?;
+
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CheckpointError> {
+        let payload = bytes
+            .strip_prefix(CHECKPOINT_MAGIC)
+            .filter(|payload| payload.len() >= 4)
+            .ok_or(CheckpointError::NotACheckpoint)?;
+
+        let (version, payload) = payload.split_at(4);
+        check_version(u32::from_le_bytes(version.try_into().unwrap()))?;
+
+        let (checkpoint, _) = bincode::serde::decode_from_slice(payload, bincode::config::standard())
+            .map_err(|err| CheckpointError::Binary(err.to_string()))?;
+
+        Ok(checkpoint)
+    }
+
+    // Either format, told apart by the binary header
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(path)?;
+
+        if bytes.starts_with(CHECKPOINT_MAGIC) {
+            Self::from_bytes(&bytes)
+        } else {
+            let json = String::from_utf8(bytes).map_err(|_| CheckpointError::NotACheckpoint)?;
+            Self::from_json(&json)
+        }
+    }
+}
+
+fn check_version(found: u32) -> Result<(), CheckpointError> {
+    if found == CHECKPOINT_VERSION {
+        Ok(())
+    } else {
+        Err(CheckpointError::UnsupportedVersion {
+            found,
+            supported: CHECKPOINT_VERSION,
+        })
+    }
+}
+
+// Written next to the target first, so that a crash mid-write can't destroy
+// what was saved there before
+pub(crate) fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), CheckpointError> {
+    // `population.json.tmp`, so that e.g. `population.json` and
+    // `population.bin` don't share a temporary file
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = PathBuf::from(temporary);
+
+    fs::write(&temporary, bytes)?;
+    fs::rename(&temporary, path)?;
+
+    Ok(())
+}
+
+// JSON has no NaN nor infinities (serde_json writes `null` instead, which
+// can't be read back), so they're written as strings there - e.g. `"-inf"`
+// for an individual that got a death penalty. Binary formats keep them as
+// they are.
+pub(crate) mod non_finite {
+    use super::*;
+
+    use serde::de::Error as _;
+
+    // Either way a JSON fitness can be written
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Readable {
+        Finite(f32),
+        NonFinite(String),
+    }
+
+    // For the fields holding more than a single fitness
+    #[derive(Serialize, Deserialize)]
+    struct Fitness(#[serde(with = "super::non_finite")] f32);
+
+    pub(crate) fn serialize<S>(value: &f32, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() && !value.is_finite() {
+            // `NaN`, `inf` or `-inf`, which `str::parse` reads back
+            serializer.serialize_str(&value.to_string())
+        } else {
+            serializer.serialize_f32(*value)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<f32, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            return f32::deserialize(deserializer);
+        }
+
+        match Readable::deserialize(deserializer)? {
+            Readable::Finite(value) => Ok(value),
+            Readable::NonFinite(text) => text
+                .parse()
+                .map_err(|_| D::Error::custom(format!("invalid fitness: {text:?}"))),
+        }
+    }
+
+    pub(crate) mod option {
+        use super::*;
+
+        pub(crate) fn serialize<S>(value: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            value.map(Fitness).serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Ok(Option::<Fitness>::deserialize(deserializer)?.map(|Fitness(value)| value))
+        }
+    }
+
+    pub(crate) mod vec {
+        use super::*;
+
+        pub(crate) fn serialize<S>(values: &[f32], serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(values.iter().map(|&value| Fitness(value)))
+        }
+
+        pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<f32>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let values = Vec::<Fitness>::deserialize(deserializer)?;
+            Ok(values.into_iter().map(|Fitness(value)| value).collect())
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    type Ga = GeneticAlgorithm<RankSelection, UniformCrossover, AdaptiveGaussianMutation>;
+
+    fn ga() -> Ga {
+        GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            AdaptiveGaussianMutation::new(0.5, 1.0, MutationControl::OneFifthRule { factor: 0.82, max_coeff: 10.0 }),
+        )
+    }
+
+    fn evolve(
+        ga: &mut Ga,
+        rng: &mut ChaCha8Rng,
+        mut population: Vec<TestIndividual>,
+        generations: usize,
+    ) -> Vec<TestIndividual> {
+        for _ in 0..generations {
+            (population, _) = Optimizer::evolve(ga, rng, &population);
+        }
+
+        population
+    }
+
+    fn genes(population: &[TestIndividual]) -> Vec<Vec<f32>> {
+        population
+            .iter()
+            .map(|individual| individual.chromosome().iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn resumes_exactly() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        let mut ga = ga();
+
+        let population: Vec<TestIndividual> = (0..8)
+            .map(|i| TestIndividual::create(vec![i as f32 / 3.0; 4].into_iter().collect()))
+            .collect();
+
+        let population = evolve(&mut ga, &mut rng, population, 3);
+
+        let json = Checkpoint::new(3, &population, ga.clone(), &rng).to_json().unwrap();
+        let bytes = Checkpoint::new(3, &population, ga.clone(), &rng).to_bytes().unwrap();
+
+        let expected = genes(&evolve(&mut ga, &mut rng, population, 5));
+
+        for checkpoint in [
+            Checkpoint::<Ga>::from_json(&json).unwrap(),
+            Checkpoint::<Ga>::from_bytes(&bytes).unwrap(),
+        ] {
+            assert_eq!(checkpoint.generation, 3);
+
+            let mut ga = checkpoint.parameters.clone();
+            let mut rng = checkpoint.rng.clone();
+            let population = checkpoint.individuals();
+
+            assert_eq!(genes(&evolve(&mut ga, &mut rng, population, 5)), expected);
+        }
+    }
+
+    #[test]
+    fn chromosomes_keep_their_genes() {
+        let chromosome: Chromosome<usize> = vec![3, 1, 2].into_iter().collect();
+
+        assert_eq!(serde_json::to_string(&chromosome).unwrap(), "[3,1,2]");
+
+        let restored: Chromosome<usize> = serde_json::from_str("[3,1,2]").unwrap();
+        assert_eq!(restored.iter().collect::<Vec<_>>(), chromosome.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let rng = ChaCha8Rng::seed_from_u64(42);
+        let population = vec![TestIndividual::create(vec![1.0].into_iter().collect())];
+
+        let checkpoint = Checkpoint::new(0, &population, (), &rng);
+
+        let json = checkpoint.to_json().unwrap().replacen("\"version\":1", "\"version\":2", 1);
+        assert!(matches!(
+            Checkpoint::<()>::from_json(&json),
+            Err(CheckpointError::UnsupportedVersion { found: 2, supported: 1 })
+        ));
+
+        let mut bytes = checkpoint.to_bytes().unwrap();
+        bytes[4] = 2;
+        assert!(matches!(
+            Checkpoint::<()>::from_bytes(&bytes),
+            Err(CheckpointError::UnsupportedVersion { found: 2, .. })
+        ));
+
+        assert!(matches!(
+            Checkpoint::<()>::from_bytes(b"{}"),
+            Err(CheckpointError::NotACheckpoint)
+        ));
+    }
+
+    #[test]
+    fn keeps_non_finite_fitness() {
+        let rng = ChaCha8Rng::seed_from_u64(42);
+
+        let fitness = [f32::NEG_INFINITY, f32::INFINITY, f32::NAN, 1.5];
+        let population: Vec<TestIndividual> = (0..4)
+            .map(|i| TestIndividual::create(vec![i as f32].into_iter().collect()))
+            .collect();
+
+        let mut checkpoint = Checkpoint::new(1, &population, ga(), &rng);
+
+        for (entry, fitness) in checkpoint.population.iter_mut().zip(fitness) {
+            entry.fitness = fitness;
+        }
+
+        let json = checkpoint.to_json().unwrap();
+        let bytes = checkpoint.to_bytes().unwrap();
+
+        assert!(json.contains("\"fitness\":\"-inf\""));
+
+        for restored in [
+            Checkpoint::<Ga>::from_json(&json).unwrap(),
+            Checkpoint::<Ga>::from_bytes(&bytes).unwrap(),
+        ] {
+            let restored: Vec<f32> = restored.population.iter().map(|entry| entry.fitness).collect();
+
+            assert_eq!(restored[..2], fitness[..2]);
+            assert!(restored[2].is_nan());
+            assert_eq!(restored[3], 1.5);
+        }
+    }
+
+    #[test]
+    fn keeps_non_finite_restart_history() {
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+
+        let mut ga = ga().with_restarts(Restarts::new(
+            Termination::Stagnation(5),
+            RestartStrategy::HyperMutation { chance: 0.5, coeff: 1.0, generations: 2 },
+        ));
+
+        // Death penalty: one individual is as bad as it gets
+        let population: Vec<TestIndividual> = [f32::NEG_INFINITY, 1.0, 2.0, 3.0]
+            .iter()
+            .map(|&gene| TestIndividual::create(vec![gene].into_iter().collect()))
+            .collect();
+
+        Optimizer::evolve(&mut ga, &mut rng, &population);
+
+        let checkpoint = Checkpoint::new(1, &population[1..], ga, &rng);
+        let json = checkpoint.to_json().unwrap();
+
+        assert!(json.contains("\"min_fitness\":\"-inf\""));
+        assert!(json.contains("\"diversity\":\"NaN\""));
+
+        let restored = Checkpoint::<Ga>::from_json(&json).unwrap();
+        assert_eq!(restored.to_json().unwrap(), json);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,371 @@
+use crate::*;
+
+use nalgebra::{
+    DMatrix,
+    DVector,
+};
+use rand_distr::{
+    Distribution,
+    StandardNormal,
+};
+
+// ----------------------- Definitions ---------------------------
+// Covariance Matrix Adaptation Evolution Strategy.
+//
+// Children are sampled from a multivariate normal distribution, whose mean,
+// covariance and step-size (sigma) are adapted every generation from the
+// best half of the evaluated population. The first call only places the mean
+// on the best individuals of the given population, so it can pick up right
+// where a GA (or `Agent::random`) left off.
+//
+// In the steady-state mode (`evolve_one`) the distribution is adapted from
+// the live population once every `population.len()` children, in between
+// every child is sampled from the same distribution.
+#[derive(Clone, Debug)]
+pub struct CmaEs {
+    initial_sigma: f64,
+    state: Option<CmaState>,
+}
+
+#[derive(Clone, Debug)]
+struct CmaState {
+    mean: DVector<f64>,
+    sigma: f64,
+    covariance: DMatrix<f64>,
+    // Eigendecomposition of the covariance, C = B * D^2 * B^T
+    basis: DMatrix<f64>,
+    scale: DVector<f64>,
+    // Evolution paths for the covariance and the step-size
+    path_c: DVector<f64>,
+    path_sigma: DVector<f64>,
+    generation: usize,
+    // Children sampled by `evolve_one` since the last update
+    births: usize,
+}
+
+// Strategy parameters, as recommended by Hansen's "The CMA Evolution
+// Strategy: A Tutorial" - they only depend on the dimension and population size
+#[derive(Clone, Debug)]
+struct Parameters {
+    weights: Vec<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chi_n: f64,
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Parameters Implementation -----------------
+impl Parameters {
+    fn new(dimensions: usize, lambda: usize) -> Self {
+        let n = dimensions as f64;
+        let mu = (lambda / 2).max(1);
+
+        let weights: Vec<f64> = (1..=mu)
+            .map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln())
+            .collect();
+        let sum: f64 = weights.iter().sum();
+        let weights: Vec<f64> = weights.into_iter().map(|w| w / sum).collect();
+
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let cc = (4.0 + mueff / n) / (n + 4.0 + 2.0 * mueff / n);
+        let cs = (mueff + 2.0) / (n + mueff + 5.0);
+        let c1 = 2.0 / ((n + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((n + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+
+        Self { weights, mueff, cc, cs, c1, cmu, damps, chi_n }
+    }
+
+    fn recombine(&self, best: &[DVector<f64>]) -> DVector<f64> {
+        best.iter()
+            .zip(&self.weights)
+            .fold(DVector::zeros(best[0].len()), |sum, (x, w)| sum + x * *w)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- CMA State Implementation -----------------
+impl CmaState {
+    fn new(mean: DVector<f64>, sigma: f64) -> Self {
+        let n = mean.len();
+
+        Self {
+            mean,
+            sigma,
+            covariance: DMatrix::identity(n, n),
+            basis: DMatrix::identity(n, n),
+            scale: DVector::from_element(n, 1.0),
+            path_c: DVector::zeros(n),
+            path_sigma: DVector::zeros(n),
+            generation: 0,
+            births: 0,
+        }
+    }
+
+    // `best` holds the `mu` best individuals, the best one first
+    fn update(&mut self, params: &Parameters, best: &[DVector<f64>]) {
+        let n = self.mean.len() as f64;
+        let Parameters { mueff, cc, cs, c1, cmu, damps, chi_n, .. } = *params;
+
+        // Step 1: Move the mean towards the best individuals
+        let steps: Vec<DVector<f64>> = best
+            .iter()
+            .map(|x| (x - &self.mean) / self.sigma)
+            .collect();
+
+        let step = params.recombine(&steps);
+        self.mean += &step * self.sigma;
+
+        // Step 2: Update the evolution paths
+        // C^(-1/2) * step = B * D^(-1) * B^T * step
+        let whitened = &self.basis * (self.basis.transpose() * &step).component_div(&self.scale);
+
+        self.path_sigma = &self.path_sigma * (1.0 - cs) + whitened * (cs * (2.0 - cs) * mueff).sqrt();
+        self.generation += 1;
+
+        let path_sigma_norm = self.path_sigma.norm();
+        let stalled = path_sigma_norm
+            / (1.0 - (1.0 - cs).powi(2 * self.generation as i32)).sqrt()
+            / chi_n
+            >= 1.4 + 2.0 / (n + 1.0);
+        let hsig = if stalled { 0.0 } else { 1.0 };
+
+        self.path_c = &self.path_c * (1.0 - cc) + &step * (hsig * (cc * (2.0 - cc) * mueff).sqrt());
+
+        // Step 3: Rank-one and rank-mu updates of the covariance
+        let rank_one = &self.path_c * self.path_c.transpose()
+            + &self.covariance * ((1.0 - hsig) * cc * (2.0 - cc));
+
+        let rank_mu = steps
+            .iter()
+            .zip(&params.weights)
+            .fold(DMatrix::zeros(self.mean.len(), self.mean.len()), |sum, (y, w)| {
+                sum + y * y.transpose() * *w
+            });
+
+        self.covariance = &self.covariance * (1.0 - c1 - cmu) + rank_one * c1 + rank_mu * cmu;
+
+        // Step 4: Step-size adaptation
+        self.sigma *= ((cs / damps) * (path_sigma_norm / chi_n - 1.0)).exp();
+
+        self.decompose();
+    }
+
+    fn decompose(&mut self) {
+        // Keep the covariance symmetric despite rounding errors
+        self.covariance = (&self.covariance + self.covariance.transpose()) * 0.5;
+
+        let eigen = self.covariance.clone().symmetric_eigen();
+
+        self.scale = eigen.eigenvalues.map(|value| value.max(1e-20).sqrt());
+        self.basis = eigen.eigenvectors;
+    }
+
+    // x = m + sigma * B * D * z, where z ~ N(0, I)
+    fn sample(&self, rng: &mut dyn RngCore) -> Chromosome {
+        let z = DVector::from_fn(self.mean.len(), |_, _| StandardNormal.sample(rng));
+        let x = &self.mean + &self.basis * self.scale.component_mul(&z) * self.sigma;
+
+        x.iter().map(|&gene| gene as f32).collect()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ---------------------- CMA-ES Implementation ------------------
+impl CmaEs {
+    pub fn new(sigma: f32) -> Self {
+        assert!(sigma > 0.0);
+
+        Self {
+            initial_sigma: sigma as f64,
+            state: None,
+        }
+    }
+
+    // Current step-size
+    pub fn sigma(&self) -> f32 {
+        match &self.state {
+            Some(state) => state.sigma as f32,
+            None => self.initial_sigma as f32,
+        }
+    }
+
+    // Current mean of the search distribution, once the first generation is in
+    pub fn mean(&self) -> Option<Chromosome> {
+        self.state
+            .as_ref()
+            .map(|state| state.mean.iter().map(|&gene| gene as f32).collect())
+    }
+
+    // Updates the distribution from the best individuals of the population,
+    // or places it on them when there's no distribution yet
+    fn adapt<I>(&mut self, population: &[I]) -> &mut CmaState
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let params = Parameters::new(population[0].chromosome().len(), population.len());
+
+        // Best individuals first
+        let mut ranked: Vec<&I> = population.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.fitness()
+                .partial_cmp(&a.fitness())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let best: Vec<DVector<f64>> = ranked
+            .into_iter()
+            .take(params.weights.len())
+            .map(|individual| {
+                let chromosome = individual.chromosome();
+                DVector::from_iterator(chromosome.len(), chromosome.iter().map(|&gene| gene as f64))
+            })
+            .collect();
+
+        match self.state.take() {
+            Some(mut state) => {
+                state.update(&params, &best);
+                state.births = 0;
+                self.state.insert(state)
+            }
+
+            None => self.state.insert(CmaState::new(params.recombine(&best), self.initial_sigma)),
+        }
+    }
+}
+
+impl Optimizer for CmaEs {
+    fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+    {
+        let state = self.adapt(population);
+
+        let new_population = (0..population.len())
+            .map(|_| I::create(state.sample(rng)))
+            .collect();
+
+        (new_population, Statistics::new(population))
+    }
+
+    fn evolve_one<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        replacement: ReplacementPolicy,
+    ) -> (usize, I)
+    where
+        I: Individual,
+    {
+        let state = match self.state.take() {
+            Some(state) if state.births < population.len() => self.state.insert(state),
+            state => {
+                self.state = state;
+                self.adapt(population)
+            }
+        };
+
+        let child = I::create(state.sample(rng));
+        state.births += 1;
+
+        (replacement.victim(rng, population), child)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::SphereIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    #[test]
+    fn converges_on_a_sphere() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut cmaes = CmaEs::new(1.0);
+
+        let mut population: Vec<SphereIndividual> = (0..10)
+            .map(|_| {
+                let genes = (0..5).map(|_| rng.random_range(4.0..6.0)).collect();
+                SphereIndividual::create(genes)
+            })
+            .collect();
+
+        let mut stats = Vec::new();
+
+        for _ in 0..150 {
+            let (next, generation) = cmaes.evolve(&mut rng, &population);
+            population = next;
+            stats.push(generation);
+        }
+
+        assert!(stats[0].max_fitness < -10.0);
+        assert!(stats.last().unwrap().max_fitness > -1e-4);
+        assert!(cmaes.sigma() < 0.1);
+
+        for gene in cmaes.mean().unwrap().iter() {
+            approx::assert_relative_eq!(*gene, 1.0, epsilon = 1e-2);
+        }
+    }
+
+    #[test]
+    fn first_generation_starts_at_the_best_individuals() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut cmaes = CmaEs::new(0.5);
+
+        let population = vec![
+            SphereIndividual::create(vec![1.0, 1.0].into_iter().collect()),
+            SphereIndividual::create(vec![9.0, 9.0].into_iter().collect()),
+        ];
+
+        let (children, _) = cmaes.evolve(&mut rng, &population);
+
+        assert_eq!(children.len(), 2);
+        assert_eq!(cmaes.mean().unwrap(), vec![1.0, 1.0].into_iter().collect());
+    }
+
+    #[test]
+    fn converges_in_the_steady_state_mode() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut cmaes = CmaEs::new(1.0);
+
+        let mut population: Vec<SphereIndividual> = (0..10)
+            .map(|_| {
+                let genes = (0..5).map(|_| rng.random_range(4.0..6.0)).collect();
+                SphereIndividual::create(genes)
+            })
+            .collect();
+
+        for birth in 0..1500 {
+            let (victim, child) = cmaes.evolve_one(&mut rng, &population, ReplacementPolicy::Worst);
+            population[victim] = child;
+
+            // The distribution only moves once every `population.len()` births
+            if birth % 10 == 0 {
+                assert_eq!(cmaes.state.as_ref().unwrap().births, 1);
+            }
+        }
+
+        let best = population.iter().map(|i| i.fitness()).fold(f32::NEG_INFINITY, f32::max);
+
+        assert!(best > -1e-2);
+        assert!(cmaes.sigma() < 1.0);
+    }
+}
+// ---------------------------------------------------------------
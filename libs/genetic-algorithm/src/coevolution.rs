@@ -0,0 +1,317 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Competitive co-evolution: several populations (e.g. predators and prey)
+// whose fitness only exists relative to each other.
+//
+// Every generation each individual plays a few opponents from every other
+// population (picked according to `Pairing`), its fitness is the average
+// score it got. Then every population is bred by its own optimizer, all of
+// them at once, so no side ever sees the next generation of another one.
+#[derive(Clone, Debug)]
+pub struct CoEvolution<O> {
+    optimizers: Vec<O>,
+    pairing: Pairing,
+    // Best individual of every population in the previous generation
+    champions: Vec<Option<Chromosome>>,
+    // Champions of the past generations of every population, the oldest
+    // first (Rosin & Belew) - scores only mean something against the
+    // opponents of their own generation, so they are never compared
+    halls_of_fame: Vec<Vec<HallOfFameEntry>>,
+    hall_of_fame_size: usize,
+    generation: usize,
+}
+
+// Who every individual plays against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pairing {
+    // The previous generation's champion of every other population
+    // (a random opponent in the very first generation)
+    AllVsBest,
+    // `n` random individuals of every other population
+    Random(usize),
+    // `n` random past champions of every other population, so that solutions
+    // can't forget how to beat opponents that went extinct
+    // (random individuals while the hall is still empty)
+    HallOfFame(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct CoEvolutionStatistics {
+    pub generation: usize,
+    pub populations: Vec<Statistics>,
+    // Games played this generation
+    pub games: usize,
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Co-Evolution Implementation ---------------
+impl<O> CoEvolution<O>
+where
+    O: Optimizer,
+{
+    // One optimizer per population; the last `hall_of_fame` champions are
+    // kept no matter the pairing
+    pub fn new(optimizers: Vec<O>, pairing: Pairing, hall_of_fame: usize) -> Self {
+        assert!(optimizers.len() >= 2, "co-evolution needs at least two populations");
+        assert!(hall_of_fame > 0);
+
+        if let Pairing::Random(n) | Pairing::HallOfFame(n) = pairing {
+            assert!(n > 0);
+        }
+
+        let populations = optimizers.len();
+
+        Self {
+            optimizers,
+            pairing,
+            champions: vec![None; populations],
+            halls_of_fame: vec![Vec::new(); populations],
+            hall_of_fame_size: hall_of_fame,
+            generation: 0,
+        }
+    }
+
+    pub fn optimizers(&self) -> &[O] {
+        &self.optimizers
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Past champions of the population, the oldest first
+    pub fn hall_of_fame(&self, population: usize) -> &[HallOfFameEntry] {
+        &self.halls_of_fame[population]
+    }
+
+    // Evaluates every population against the others and breeds their next
+    // generation.
+    //
+    // `play(side, player, opponent_side, opponent)` returns the score of
+    // `player` (from population `side`) against `opponent`; higher is better.
+    pub fn evolve<F>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        populations: &[Vec<Chromosome>],
+        mut play: F,
+    ) -> (Vec<Vec<Chromosome>>, CoEvolutionStatistics)
+    where
+        F: FnMut(usize, &Chromosome, usize, &Chromosome) -> f32,
+    {
+        assert_eq!(populations.len(), self.optimizers.len());
+        assert!(populations.iter().all(|population| !population.is_empty()));
+
+        let mut games = 0;
+
+        // Step 1: Evaluate everyone against the current opponents
+        let scored: Vec<Vec<ScoredIndividual>> = populations
+            .iter()
+            .enumerate()
+            .map(|(side, population)| {
+                population
+                    .iter()
+                    .map(|player| {
+                        let mut total = 0.0;
+                        let mut count = 0;
+
+                        for (other, opponents) in populations.iter().enumerate() {
+                            if other == side {
+                                continue;
+                            }
+
+                            for opponent in self.opponents(rng, other, opponents) {
+                                total += play(side, player, other, opponent);
+                                count += 1;
+                            }
+                        }
+
+                        games += count;
+
+                        ScoredIndividual {
+                            chromosome: player.clone(),
+                            score: total / count as f32,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Step 2: Remember the champions
+        for (side, population) in scored.iter().enumerate() {
+            let champion = population
+                .iter()
+                .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+                .expect("got an empty population");
+
+            let hall = &mut self.halls_of_fame[side];
+            let known = hall
+                .iter()
+                .any(|member| member.chromosome.distance(&champion.chromosome) == 0.0);
+
+            if !known {
+                if hall.len() >= self.hall_of_fame_size {
+                    hall.remove(0);
+                }
+
+                hall.push(HallOfFameEntry {
+                    chromosome: champion.chromosome.clone(),
+                    fitness: champion.score,
+                    generation: self.generation,
+                });
+            }
+
+            self.champions[side] = Some(champion.chromosome.clone());
+        }
+
+        // Step 3: Breed every population on its own
+        let (new_populations, stats): (Vec<_>, Vec<_>) = self
+            .optimizers
+            .iter_mut()
+            .zip(&scored)
+            .map(|(optimizer, population)| {
+                let (children, stats) = optimizer.evolve(rng, population);
+                let children = children.into_iter().map(|child| child.chromosome).collect();
+
+                (children, stats)
+            })
+            .unzip();
+
+        let stats = CoEvolutionStatistics {
+            generation: self.generation,
+            populations: stats,
+            games,
+        };
+
+        self.generation += 1;
+
+        (new_populations, stats)
+    }
+
+    fn opponents<'a>(
+        &'a self,
+        rng: &mut dyn RngCore,
+        side: usize,
+        population: &'a [Chromosome],
+    ) -> Vec<&'a Chromosome> {
+        let sample = |rng: &mut dyn RngCore, n: usize| population.choose_multiple(rng, n).collect();
+
+        match self.pairing {
+            Pairing::AllVsBest => match &self.champions[side] {
+                Some(champion) => vec![champion],
+                None => sample(rng, 1),
+            },
+
+            Pairing::Random(n) => sample(rng, n),
+
+            Pairing::HallOfFame(n) => {
+                let hall = &self.halls_of_fame[side];
+
+                if hall.is_empty() {
+                    sample(rng, n)
+                } else {
+                    hall.choose_multiple(rng, n).map(|member| &member.chromosome).collect()
+                }
+            }
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn ga() -> GeneticAlgorithm<RankSelection, UniformCrossover, GaussianMutation> {
+        GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.5, 0.2))
+    }
+
+    fn populations() -> Vec<Vec<Chromosome>> {
+        let population = |offset: f32| (0..6).map(|i| vec![offset + i as f32].into_iter().collect()).collect();
+        vec![population(0.0), population(10.0)]
+    }
+
+    // Predators catch slower prey, prey escape slower predators - whoever
+    // has the higher first gene (speed) wins
+    fn chase(_: usize, player: &Chromosome, _: usize, opponent: &Chromosome) -> f32 {
+        player[0] - opponent[0]
+    }
+
+    #[test]
+    fn every_side_plays_the_others() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut coevolution = CoEvolution::new(vec![ga(), ga(), ga()], Pairing::Random(2), 5);
+
+        let mut populations = populations();
+        populations.push(populations[0].clone());
+
+        let mut sides = Vec::new();
+        let (children, stats) = coevolution.evolve(&mut rng, &populations, |side, _, other, _| {
+            sides.push((side, other));
+            0.0
+        });
+
+        assert_eq!(children.iter().map(Vec::len).collect::<Vec<_>>(), [6, 6, 6]);
+        assert_eq!(stats.games, 3 * 6 * 2 * 2);
+        assert!(sides.iter().all(|(side, other)| side != other));
+        assert_eq!(coevolution.generation(), 1);
+    }
+
+    #[test]
+    fn all_vs_best_plays_the_last_champion() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut coevolution = CoEvolution::new(vec![ga(), ga()], Pairing::AllVsBest, 5);
+
+        let (populations, stats) = coevolution.evolve(&mut rng, &populations(), chase);
+        assert_eq!(stats.games, 2 * 6);
+
+        let champion = coevolution.champions[1].clone().unwrap();
+
+        coevolution.evolve(&mut rng, &populations, |side, _, _, opponent| {
+            if side == 0 {
+                assert_eq!(opponent.iter().collect::<Vec<_>>(), champion.iter().collect::<Vec<_>>());
+            }
+            0.0
+        });
+    }
+
+    #[test]
+    fn hall_of_fame_opponents() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut coevolution = CoEvolution::new(vec![ga(), ga()], Pairing::HallOfFame(3), 3);
+
+        let (mut populations, _) = coevolution.evolve(&mut rng, &populations(), chase);
+
+        // Only the champion gets in, however well the rest did
+        assert_eq!(coevolution.hall_of_fame(0).len(), 1);
+        assert_eq!(coevolution.hall_of_fame(1)[0].chromosome[0], 15.0);
+
+        for _ in 0..9 {
+            (populations, _) = coevolution.evolve(&mut rng, &populations, chase);
+        }
+
+        // The oldest champions make room for the new ones, no matter their
+        // scores
+        let generations: Vec<usize> = coevolution.hall_of_fame(1).iter().map(|m| m.generation).collect();
+        assert_eq!(generations.len(), 3);
+        assert!(generations.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(generations[0] > 0);
+
+        let hall: Vec<f32> = coevolution.hall_of_fame(1).iter().map(|m| m.chromosome[0]).collect();
+
+        coevolution.evolve(&mut rng, &populations, |side, _, _, opponent| {
+            if side == 0 {
+                assert!(hall.contains(&opponent[0]));
+            }
+            0.0
+        });
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,409 @@
+use crate::*;
+
+use std::ops::Range;
+
+// ----------------------- Definitions ---------------------------
+// Per-gene limits, so that e.g. neural network weights can't drift away to
+// ±1000 over many generations.
+//
+// Every gene can be left unbounded, share the default bounds or belong to a
+// group with its own bounds (later groups override earlier ones).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bounds {
+    default: Option<(f32, f32)>,
+    groups: Vec<(Range<usize>, f32, f32)>,
+    repair: Repair,
+}
+
+// What happens to a gene that ended up outside of its bounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Repair {
+    // Moved onto the nearest bound
+    Clamp,
+    // Bounced back from the bound it crossed (as many times as needed)
+    Reflect,
+    // Re-enters from the opposite bound
+    Wrap,
+}
+
+// Wraps a crossover or mutation method and repairs every chromosome it
+// produces, so the operator never hands out-of-range genes to the GA
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Bounded<O> {
+    operator: O,
+    bounds: Bounds,
+}
+
+// Individual with user-defined constraints, e.g. "the weights of a neuron
+// must sum up to at most 1.0"
+pub trait ConstrainedIndividual: Individual {
+    // How badly the constraints are violated; 0.0 means feasible
+    fn violation(&self) -> f32;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConstraintHandling {
+    // fitness - coefficient * violation
+    Penalty { coefficient: f32 },
+    // Deb's rules: feasible beats infeasible, two feasible individuals are
+    // compared by fitness and two infeasible ones by their violation
+    FeasibilityRules,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstraintStatistics {
+    // Raw fitness, as reported by the individuals
+    pub fitness: Statistics,
+    pub feasible: usize,
+    pub avg_violation: f32,
+    pub max_violation: f32,
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Bounds Implementation --------------------
+impl Bounds {
+    // No gene is bounded until `with_all`, `with_gene` or `with_group`
+    pub fn new(repair: Repair) -> Self {
+        Self {
+            default: None,
+            groups: Vec::new(),
+            repair,
+        }
+    }
+
+    pub fn with_all(mut self, min: f32, max: f32) -> Self {
+        assert!(min < max);
+        self.default = Some((min, max));
+        self
+    }
+
+    pub fn with_gene(self, index: usize, min: f32, max: f32) -> Self {
+        self.with_group(index..index + 1, min, max)
+    }
+
+    pub fn with_group(mut self, genes: Range<usize>, min: f32, max: f32) -> Self {
+        assert!(min < max);
+        self.groups.push((genes, min, max));
+        self
+    }
+
+    pub fn bound(&self, index: usize) -> Option<(f32, f32)> {
+        self.groups
+            .iter()
+            .rev()
+            .find(|(genes, _, _)| genes.contains(&index))
+            .map(|&(_, min, max)| (min, max))
+            .or(self.default)
+    }
+
+    pub fn contains(&self, chromosome: &Chromosome) -> bool {
+        chromosome.iter().enumerate().all(|(index, gene)| {
+            self.bound(index)
+                .is_none_or(|(min, max)| (min..=max).contains(gene))
+        })
+    }
+
+    pub fn repair(&self, chromosome: &mut Chromosome) {
+        for (index, gene) in chromosome.iter_mut().enumerate() {
+            if let Some((min, max)) = self.bound(index) {
+                *gene = self.repair.apply(*gene, min, max);
+            }
+        }
+    }
+}
+
+impl Repair {
+    fn apply(self, gene: f32, min: f32, max: f32) -> f32 {
+        if (min..=max).contains(&gene) {
+            return gene;
+        }
+
+        let width = max - min;
+
+        match self {
+            Repair::Clamp => gene.clamp(min, max),
+
+            Repair::Reflect => {
+                // Reflecting is periodic with a period of two widths
+                let offset = (gene - min).rem_euclid(2.0 * width);
+
+                if offset > width {
+                    min + 2.0 * width - offset
+                } else {
+                    min + offset
+                }
+            }
+
+            Repair::Wrap => min + (gene - min).rem_euclid(width),
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Bounded Implementation --------------------
+impl<O> Bounded<O> {
+    pub fn new(operator: O, bounds: Bounds) -> Self {
+        Self { operator, bounds }
+    }
+
+    pub fn bounds(&self) -> &Bounds {
+        &self.bounds
+    }
+}
+
+impl<C> CrossoverMethod for Bounded<C>
+where
+    C: CrossoverMethod,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        let mut child = self.operator.crossover(rng, parent_a, parent_b);
+        self.bounds.repair(&mut child);
+        child
+    }
+
+    fn operators(&self) -> usize {
+        self.operator.operators()
+    }
+
+    fn crossover_with(
+        &self,
+        rng: &mut dyn RngCore,
+        operator: usize,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        let mut child = self.operator.crossover_with(rng, operator, parent_a, parent_b);
+        self.bounds.repair(&mut child);
+        child
+    }
+}
+
+impl<M> MutationMethod for Bounded<M>
+where
+    M: MutationMethod,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        self.operator.mutate(rng, child);
+        self.bounds.repair(child);
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        self.operator.adapt(feedback);
+    }
+
+    fn parameters(&self) -> Option<MutationParameters> {
+        self.operator.parameters()
+    }
+
+    fn operators(&self) -> usize {
+        self.operator.operators()
+    }
+
+    fn mutate_with(&self, rng: &mut dyn RngCore, operator: usize, child: &mut Chromosome) {
+        self.operator.mutate_with(rng, operator, child);
+        self.bounds.repair(child);
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------- Constraint Handling Implementation ------------
+impl ConstraintHandling {
+    // Turns fitness and violation into a single score to select on
+    pub fn scores<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: ConstrainedIndividual,
+    {
+        match *self {
+            ConstraintHandling::Penalty { coefficient } => population
+                .iter()
+                .map(|individual| individual.fitness() - coefficient * individual.violation())
+                .collect(),
+
+            ConstraintHandling::FeasibilityRules => {
+                // Every infeasible individual lands below the worst feasible
+                // one, ordered by how much it violates the constraints
+                let worst_feasible = population
+                    .iter()
+                    .filter(|individual| individual.violation() <= 0.0)
+                    .map(I::fitness)
+                    .fold(f32::INFINITY, f32::min);
+
+                let base = if worst_feasible.is_finite() { worst_feasible } else { 0.0 };
+
+                let mut scores: Vec<f32> = population.iter().map(I::fitness).collect();
+                let violations: Vec<f32> = population.iter().map(I::violation).collect();
+
+                let mut infeasible: Vec<usize> =
+                    (0..population.len()).filter(|&index| violations[index] > 0.0).collect();
+
+                infeasible.sort_by(|&a, &b| violations[a].total_cmp(&violations[b]));
+
+                // `base - violation` gets rounded back to `base` once the
+                // fitness is big enough (e.g. 1e8 - 1.0), so every worse
+                // violation is forced at least one step further down
+                let mut previous: Option<(f32, f32)> = None;
+
+                for index in infeasible {
+                    let violation = violations[index];
+
+                    let score = match previous {
+                        Some((previous_violation, score)) if previous_violation == violation => score,
+                        Some((_, score)) => (base - violation).min(score.next_down()),
+                        None => (base - violation).min(base.next_down()),
+                    };
+
+                    scores[index] = score;
+                    previous = Some((violation, score));
+                }
+
+                scores
+            }
+        }
+    }
+
+    // Lets `optimizer` breed the next generation on the constrained score
+    pub fn evolve<O, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        optimizer: &mut O,
+        population: &[I],
+    ) -> (Vec<I>, ConstraintStatistics)
+    where
+        O: Optimizer,
+        I: ConstrainedIndividual,
+    {
+        assert!(!population.is_empty());
+
+        let scores = self.scores(population);
+        let new_population = evolve_scored(rng, optimizer, population, &scores);
+
+        let violations: Vec<f32> = population.iter().map(I::violation).collect();
+
+        let stats = ConstraintStatistics {
+            fitness: Statistics::new(population),
+            feasible: violations.iter().filter(|&&violation| violation <= 0.0).count(),
+            avg_violation: violations.iter().sum::<f32>() / violations.len() as f32,
+            max_violation: violations.iter().cloned().fold(0.0, f32::max),
+        };
+
+        (new_population, stats)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Constrained;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn repaired(repair: Repair, genes: &[f32]) -> Vec<f32> {
+        let bounds = Bounds::new(repair).with_all(-1.0, 1.0).with_gene(0, 0.0, 10.0);
+
+        let mut chromosome: Chromosome = genes.iter().cloned().collect();
+        bounds.repair(&mut chromosome);
+
+        chromosome.iter().cloned().collect()
+    }
+
+    #[test]
+    fn repairs() {
+        approx::assert_relative_eq!(
+            repaired(Repair::Clamp, &[12.0, 0.5, -3.5]).as_slice(),
+            [10.0, 0.5, -1.0].as_ref()
+        );
+
+        approx::assert_relative_eq!(
+            repaired(Repair::Reflect, &[12.0, 0.5, -3.5]).as_slice(),
+            [8.0, 0.5, 0.5].as_ref()
+        );
+
+        approx::assert_relative_eq!(
+            repaired(Repair::Wrap, &[12.0, 0.5, -3.5]).as_slice(),
+            [2.0, 0.5, 0.5].as_ref()
+        );
+    }
+
+    #[test]
+    fn bounded_operators_stay_within_bounds() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let bounds = Bounds::new(Repair::Reflect).with_all(-1.0, 1.0);
+
+        let crossover = Bounded::new(UniformCrossover, bounds.clone());
+        let mutation = Bounded::new(GaussianMutation::new(1.0, 100.0), bounds.clone());
+
+        let parent_a: Chromosome = vec![5.0; 10].into_iter().collect();
+        let parent_b: Chromosome = vec![-5.0; 10].into_iter().collect();
+
+        let mut child = crossover.crossover(&mut rng, &parent_a, &parent_b);
+        assert!(bounds.contains(&child));
+
+        mutation.mutate(&mut rng, &mut child);
+        assert!(bounds.contains(&child));
+    }
+
+    fn population() -> Vec<Constrained> {
+        [[-1.0, 1.0], [-1.0, 4.0], [2.0, 5.0], [1.0, 1.0]]
+            .iter()
+            .map(|genes| Constrained::create(genes.iter().cloned().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn scores() {
+        let penalty = ConstraintHandling::Penalty { coefficient: 2.0 }.scores(&population());
+        approx::assert_relative_eq!(penalty.as_slice(), [0.0, 3.0, 3.0, 0.0].as_ref());
+
+        // Worst feasible fitness is 0.0
+        let rules = ConstraintHandling::FeasibilityRules.scores(&population());
+        approx::assert_relative_eq!(rules.as_slice(), [0.0, 3.0, -2.0, -1.0].as_ref());
+    }
+
+    #[test]
+    fn feasibility_rules_survive_large_fitness() {
+        let population: Vec<Constrained> = [[-1.0, 1e8], [0.5, 1e8], [1.0, 1e8], [1.0, 2e8]]
+            .iter()
+            .map(|genes| Constrained::create(genes.iter().cloned().collect()))
+            .collect();
+
+        let scores = ConstraintHandling::FeasibilityRules.scores(&population);
+
+        assert!(scores[0] > scores[1]);
+        assert!(scores[1] > scores[2]);
+        assert_eq!(scores[2], scores[3]);
+    }
+
+    #[test]
+    fn evolve_reports_violations() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let (children, stats) =
+            ConstraintHandling::FeasibilityRules.evolve(&mut rng, &mut ga, &population());
+
+        assert_eq!(children.len(), 4);
+        assert_eq!(stats.feasible, 2);
+        approx::assert_relative_eq!(stats.avg_violation, 0.75);
+        approx::assert_relative_eq!(stats.max_violation, 2.0);
+        approx::assert_relative_eq!(stats.fitness.max_fitness, 7.0);
+    }
+}
+// ---------------------------------------------------------------
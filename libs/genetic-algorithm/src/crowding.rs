@@ -0,0 +1,460 @@
+use crate::*;
+
+use rand::seq::SliceRandom;
+
+// ----------------------- Definitions ---------------------------
+// Niching through replacement: children don't replace the whole population,
+// each one competes with a similar individual and only takes its place if
+// it's at least as fit, so separate peaks keep their own sub-populations.
+//
+// Like `DifferentialEvolution`, `evolve` returns the children: hand them
+// back evaluated, in the same order, on the next call. The first call treats
+// the given population as the residents.
+#[derive(Clone, Debug)]
+pub struct Crowding<S, C, M> {
+    ga: GeneticAlgorithm<S, C, M>,
+    replacement: CrowdingReplacement,
+    state: Option<CrowdingState>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrowdingReplacement {
+    // Mahfoud's deterministic crowding: residents are paired at random, every
+    // pair breeds two children and each child competes with the parent it's
+    // closer to (selection method isn't used)
+    Deterministic,
+    // Harik's restricted tournament replacement: parents are picked by the
+    // selection method, every child competes with the closest of `window`
+    // random residents
+    RestrictedTournament { window: usize },
+}
+
+// Children closer than `epsilon` to one of their parents' population or to
+// an earlier child are considered clones
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateFilter {
+    epsilon: f32,
+    action: DuplicateAction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateAction {
+    // Mutated again, up to `attempts` times (it's kept if it's still a clone)
+    Remutate { attempts: usize },
+    // Swapped for random genes within the population's range of every gene
+    Replace,
+}
+
+#[derive(Clone, Debug)]
+struct CrowdingState {
+    residents: Vec<ScoredIndividual>,
+    // Resident every pending child competes with (only used by deterministic
+    // crowding, restricted tournaments pick theirs once the child is back)
+    rivals: Vec<usize>,
+}
+// ---------------------------------------------------------------
+
+
+// ---------------------- Crowding Implementation ----------------
+impl<S, C, M> Crowding<S, C, M>
+where
+    S: SelectionMethod,
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    // Children are bred with the GA's operators and go through its
+    // duplicate filter, if it has one
+    pub fn new(ga: GeneticAlgorithm<S, C, M>, replacement: CrowdingReplacement) -> Self {
+        if let CrowdingReplacement::RestrictedTournament { window } = replacement {
+            assert!(window > 0);
+        }
+
+        Self {
+            ga,
+            replacement,
+            state: None,
+        }
+    }
+
+    pub fn replacement(&self) -> CrowdingReplacement {
+        self.replacement
+    }
+
+    // Current population with the fitness it was evaluated with
+    pub fn residents(&self) -> impl Iterator<Item = (&Chromosome, f32)> {
+        self.state
+            .iter()
+            .flat_map(|state| &state.residents)
+            .map(|resident| (&resident.chromosome, resident.score))
+    }
+
+    fn replace(
+        &self,
+        rng: &mut dyn RngCore,
+        state: &mut CrowdingState,
+        children: Vec<ScoredIndividual>,
+    ) {
+        let residents = &mut state.residents;
+
+        for (index, child) in children.into_iter().enumerate() {
+            let rival = match self.replacement {
+                CrowdingReplacement::Deterministic => state.rivals[index],
+
+                CrowdingReplacement::RestrictedTournament { window } => {
+                    rand::seq::index::sample(rng, residents.len(), window.min(residents.len()))
+                        .into_iter()
+                        .min_by(|&a, &b| {
+                            let distance = |resident: usize| {
+                                residents[resident].chromosome.distance(&child.chromosome)
+                            };
+
+                            distance(a)
+                                .partial_cmp(&distance(b))
+                                .unwrap_or(std::cmp::Ordering::Equal)
+                        })
+                        .expect("got an empty population")
+                }
+            };
+
+            if child.score >= residents[rival].score {
+                residents[rival] = child;
+            }
+        }
+    }
+
+    // Random pairs, two children per pair (a single one for the odd resident
+    // left out), each matched with the closer parent
+    fn breed_pairs(
+        &self,
+        rng: &mut dyn RngCore,
+        residents: &[ScoredIndividual],
+    ) -> (Vec<Chromosome>, Vec<usize>) {
+        let mut order: Vec<usize> = (0..residents.len()).collect();
+        order.shuffle(rng);
+
+        let mut children = Vec::with_capacity(residents.len());
+        let mut rivals = Vec::with_capacity(residents.len());
+
+        for pair in order.chunks(2) {
+            let &[a, b] = pair else {
+                let mut child = residents[pair[0]].chromosome.clone();
+                self.ga.mutation_method.mutate(rng, &mut child);
+
+                children.push(child);
+                rivals.push(pair[0]);
+                continue;
+            };
+
+            let (parent_a, parent_b) = (&residents[a].chromosome, &residents[b].chromosome);
+
+            let mut child_a = self.ga.crossover_method.crossover(rng, parent_a, parent_b);
+            let mut child_b = self.ga.crossover_method.crossover(rng, parent_b, parent_a);
+            self.ga.mutation_method.mutate(rng, &mut child_a);
+            self.ga.mutation_method.mutate(rng, &mut child_b);
+
+            let straight = child_a.distance(parent_a) + child_b.distance(parent_b);
+            let crossed = child_a.distance(parent_b) + child_b.distance(parent_a);
+
+            children.extend([child_a, child_b]);
+            rivals.extend(if straight <= crossed { [a, b] } else { [b, a] });
+        }
+
+        (children, rivals)
+    }
+}
+
+impl<S, C, M> Optimizer for Crowding<S, C, M>
+where
+    S: SelectionMethod,
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let scored = population.iter().map(|individual| ScoredIndividual {
+            chromosome: individual.chromosome().clone(),
+            score: individual.fitness(),
+        });
+
+        // Step 1: Let the evaluated children compete with the residents
+        let mut state = match self.state.take() {
+            Some(mut state) => {
+                assert_eq!(state.rivals.len(), population.len());
+
+                self.replace(rng, &mut state, scored.collect());
+                state
+            }
+
+            None => CrowdingState {
+                residents: scored.collect(),
+                rivals: Vec::new(),
+            },
+        };
+
+        let mut stats = Statistics::new(&state.residents);
+        stats.mutation = self.ga.mutation_method.parameters();
+
+        // Step 2: Breed the next children
+        let (children, rivals) = match self.replacement {
+            CrowdingReplacement::Deterministic => self.breed_pairs(rng, &state.residents),
+
+            CrowdingReplacement::RestrictedTournament { .. } => {
+                let children = (0..state.residents.len())
+                    .map(|child| self.ga.breed(rng, &state.residents, child, |parent| parent).0)
+                    .collect();
+
+                (children, vec![0; state.residents.len()])
+            }
+        };
+
+        let mut children: Vec<I> = children.into_iter().map(I::create).collect();
+
+        if let Some(duplicates) = &self.ga.duplicates {
+            duplicates.filter(rng, &self.ga.mutation_method, &state.residents, &mut children);
+        }
+
+        state.rivals = rivals;
+        self.state = Some(state);
+
+        (children, stats)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Duplicate Filter Implementation -----------
+impl DuplicateFilter {
+    pub fn new(epsilon: f32, action: DuplicateAction) -> Self {
+        assert!(epsilon >= 0.0);
+
+        if let DuplicateAction::Remutate { attempts } = action {
+            assert!(attempts > 0);
+        }
+
+        Self { epsilon, action }
+    }
+
+    // Handles every child that's a clone of someone in `population` or of an
+    // earlier child, returns their indices
+    pub fn filter<P, I, G, M>(
+        &self,
+        rng: &mut dyn RngCore,
+        mutation: &M,
+        population: &[P],
+        children: &mut [I],
+    ) -> Vec<usize>
+    where
+        P: Individual<G>,
+        I: Individual<G>,
+        G: Gene,
+        M: MutationMethod<G>,
+    {
+        let mut duplicates = Vec::new();
+
+        for index in 0..children.len() {
+            let (earlier, rest) = children.split_at_mut(index);
+            let child = &mut rest[0];
+
+            let is_clone = |chromosome: &Chromosome<G>| {
+                population
+                    .iter()
+                    .map(P::chromosome)
+                    .chain(earlier.iter().map(I::chromosome))
+                    .any(|other| other.distance(chromosome) <= self.epsilon)
+            };
+
+            if !is_clone(child.chromosome()) {
+                continue;
+            }
+
+            duplicates.push(index);
+
+            let chromosome = match self.action {
+                DuplicateAction::Remutate { attempts } => {
+                    let mut chromosome = child.chromosome().clone();
+
+                    for _ in 0..attempts {
+                        mutation.mutate(rng, &mut chromosome);
+
+                        if !is_clone(&chromosome) {
+                            break;
+                        }
+                    }
+
+                    chromosome
+                }
+
+                DuplicateAction::Replace => Chromosome::from_real(Self::random_within(
+                    rng,
+                    population,
+                    &child.chromosome().to_real(),
+                )),
+            };
+
+            *child = I::create(chromosome);
+        }
+
+        duplicates
+    }
+
+    fn random_within<P, G>(rng: &mut dyn RngCore, population: &[P], fallback: &Chromosome) -> Chromosome
+    where
+        P: Individual<G>,
+        G: Gene,
+    {
+        let population: Vec<Chromosome> = population
+            .iter()
+            .map(|individual| individual.chromosome().to_real())
+            .collect();
+
+        (0..fallback.len())
+            .map(|gene| {
+                let values = population.iter().map(|chromosome| chromosome[gene]);
+                let min = values.clone().fold(f32::INFINITY, f32::min);
+                let max = values.fold(f32::NEG_INFINITY, f32::max);
+
+                if min < max {
+                    rng.random_range(min..=max)
+                } else {
+                    fallback[gene]
+                }
+            })
+            .collect()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{BitIndividual, TestIndividual, TwoPeaksIndividual};
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn ga() -> GeneticAlgorithm<RankSelection, UniformCrossover, GaussianMutation> {
+        GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.5, 0.5))
+    }
+
+    fn run(mut crowding: Crowding<RankSelection, UniformCrossover, GaussianMutation>) -> Vec<Statistics> {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut population: Vec<TwoPeaksIndividual> = (0..30)
+            .map(|_| TwoPeaksIndividual::create((0..2).map(|_| rng.random_range(-8.0..8.0)).collect()))
+            .collect();
+
+        let mut stats = Vec::new();
+
+        for _ in 0..100 {
+            let generation;
+            (population, generation) = crowding.evolve(&mut rng, &population);
+            stats.push(generation);
+        }
+
+        let residents: Vec<f32> = crowding.residents().map(|(chromosome, _)| chromosome[0]).collect();
+
+        // Both peaks are still populated
+        assert!(residents.iter().filter(|&&x| x < -4.0).count() >= 3, "{residents:?}");
+        assert!(residents.iter().filter(|&&x| x > 4.0).count() >= 3, "{residents:?}");
+
+        stats
+    }
+
+    #[test]
+    fn crowding_keeps_both_peaks() {
+        for replacement in [
+            CrowdingReplacement::Deterministic,
+            CrowdingReplacement::RestrictedTournament { window: 10 },
+        ] {
+            let stats = run(Crowding::new(ga(), replacement));
+
+            for pair in stats.windows(2) {
+                assert!(pair[1].max_fitness >= pair[0].max_fitness);
+            }
+        }
+    }
+
+    #[test]
+    fn duplicates_are_remutated_or_replaced() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let population: Vec<TestIndividual> = [[0.0, 0.0], [4.0, 2.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+            .collect();
+
+        let children = || -> Vec<TestIndividual> {
+            [[0.0, 0.0], [1.0, 1.0], [1.0, 1.05]]
+                .iter()
+                .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+                .collect()
+        };
+
+        let mutation = GaussianMutation::new(1.0, 10.0);
+
+        let mut remutated = children();
+        let filter = DuplicateFilter::new(0.1, DuplicateAction::Remutate { attempts: 5 });
+        assert_eq!(filter.filter(&mut rng, &mutation, &population, &mut remutated).len(), 2);
+        assert_eq!(remutated[1], children()[1]);
+        assert_ne!(remutated[0], children()[0]);
+        assert_ne!(remutated[2], children()[2]);
+
+        let mut replaced = children();
+        let filter = DuplicateFilter::new(0.1, DuplicateAction::Replace);
+        assert_eq!(filter.filter(&mut rng, &mutation, &population, &mut replaced).len(), 2);
+
+        for child in [&replaced[0], &replaced[2]] {
+            assert!((0.0..=4.0).contains(&child.chromosome()[0]));
+            assert!((0.0..=2.0).contains(&child.chromosome()[1]));
+        }
+    }
+
+    #[test]
+    fn genetic_algorithm_filters_its_children() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        // Without mutation, two parents can only have four different children
+        let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.0, 0.0))
+            .with_duplicate_filter(DuplicateFilter::new(0.0, DuplicateAction::Replace));
+
+        let population: Vec<TestIndividual> = [[1.0, 2.0], [3.0, 0.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+            .collect();
+
+        let (children, _) = Optimizer::evolve(&mut ga, &mut rng, &population);
+
+        for (index, child) in children.iter().enumerate() {
+            let distance = |other: &TestIndividual| other.chromosome().distance(child.chromosome());
+
+            assert!(population.iter().all(|parent| distance(parent) > 0.0));
+            assert!(children[..index].iter().all(|other| distance(other) > 0.0));
+        }
+    }
+
+    #[test]
+    fn replaces_binary_duplicates() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, BitFlipMutation::new(0.0))
+            .with_duplicate_filter(DuplicateFilter::new(0.0, DuplicateAction::Replace));
+
+        let population: Vec<BitIndividual> = [[true, false, false], [false, false, true]]
+            .iter()
+            .map(|genes| BitIndividual::create(genes.iter().cloned().collect()))
+            .collect();
+
+        let (children, stats) = Optimizer::evolve(&mut ga, &mut rng, &population);
+
+        assert_eq!(children.len(), 2);
+        assert!(stats.max_fitness >= 1.0);
+    }
+}
+// ---------------------------------------------------------------
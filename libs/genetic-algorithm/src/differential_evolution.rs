@@ -0,0 +1,330 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Differential Evolution.
+//
+// Every individual (target) gets a trial vector built from the scaled
+// difference of other individuals, crossed over with the target. Once the
+// trials are evaluated, each one replaces its own target if it's at least
+// as good (one-to-one survivor selection).
+//
+// `evolve` returns the trials: hand them back evaluated, in the same order,
+// on the next call. The first call treats the given population as targets.
+//
+// The steady-state mode (`evolve_one`) doesn't keep any targets: the child
+// is a trial built for the individual it's going to replace, straight from
+// the live population, with the configured F and CR.
+#[derive(Clone, Debug)]
+pub struct DifferentialEvolution {
+    strategy: DeStrategy,
+    // Differential weight (F) and crossover rate (CR)
+    weight: f32,
+    crossover_rate: f32,
+    self_adaptive: bool,
+    state: Option<DeState>,
+}
+
+// How the mutant vector is built, always followed by binomial crossover
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeStrategy {
+    // r1 + F * (r2 - r3)
+    Rand1Bin,
+    // best + F * (r1 - r2)
+    Best1Bin,
+    // target + F * (best - target) + F * (r1 - r2)
+    CurrentToBest1,
+}
+
+#[derive(Clone, Debug)]
+struct DeState {
+    targets: Vec<ScoredIndividual>,
+    // F and CR of every target, they only differ with jDE
+    controls: Vec<(f32, f32)>,
+    // F and CR the pending trials were built with
+    trial_controls: Vec<(f32, f32)>,
+}
+// ---------------------------------------------------------------
+
+
+// ---------------- Differential Evolution Implementation --------
+// jDE: chance of resampling F and CR, and the range F is resampled from
+const JDE_TAU: f32 = 0.1;
+const JDE_WEIGHT_MIN: f32 = 0.1;
+const JDE_WEIGHT_MAX: f32 = 1.0;
+
+impl DifferentialEvolution {
+    pub fn new(strategy: DeStrategy, weight: f32, crossover_rate: f32) -> Self {
+        assert!(weight > 0.0 && weight <= 2.0);
+        assert!((0.0..=1.0).contains(&crossover_rate));
+
+        Self {
+            strategy,
+            weight,
+            crossover_rate,
+            self_adaptive: false,
+            state: None,
+        }
+    }
+
+    // jDE (Brest et al.): every individual carries its own F and CR, starting
+    // at the configured ones. Before building a trial, each of them is
+    // resampled with a 10% chance and it survives only if the trial does.
+    pub fn with_jde(mut self) -> Self {
+        self.self_adaptive = true;
+        self
+    }
+
+    // Best surviving target so far, with its fitness
+    pub fn best(&self) -> Option<(&Chromosome, f32)> {
+        let state = self.state.as_ref()?;
+
+        state
+            .targets
+            .iter()
+            .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|target| (&target.chromosome, target.score))
+    }
+
+    // F and CR of every target
+    pub fn controls(&self) -> &[(f32, f32)] {
+        self.state.as_ref().map_or(&[], |state| &state.controls)
+    }
+
+    fn trial(
+        &self,
+        rng: &mut dyn RngCore,
+        targets: &[ScoredIndividual],
+        best: usize,
+        index: usize,
+        (weight, crossover_rate): (f32, f32),
+    ) -> Chromosome {
+        // Three distinct individuals, none of them being the target
+        let picked: Vec<usize> = rand::seq::index::sample(rng, targets.len() - 1, 3)
+            .into_iter()
+            .map(|other| if other >= index { other + 1 } else { other })
+            .collect();
+
+        let gene = |individual: usize, gene: usize| targets[individual].chromosome[gene];
+        let (r1, r2, r3) = (picked[0], picked[1], picked[2]);
+
+        let target = &targets[index].chromosome;
+        let forced = rng.random_range(0..target.len());
+
+        (0..target.len())
+            .map(|j| {
+                if j != forced && rng.random::<f32>() >= crossover_rate {
+                    return target[j];
+                }
+
+                match self.strategy {
+                    DeStrategy::Rand1Bin => gene(r1, j) + weight * (gene(r2, j) - gene(r3, j)),
+                    DeStrategy::Best1Bin => gene(best, j) + weight * (gene(r1, j) - gene(r2, j)),
+                    DeStrategy::CurrentToBest1 => {
+                        target[j]
+                            + weight * (gene(best, j) - target[j])
+                            + weight * (gene(r1, j) - gene(r2, j))
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn control(&self, rng: &mut dyn RngCore, current: (f32, f32)) -> (f32, f32) {
+        if !self.self_adaptive {
+            return current;
+        }
+
+        let (mut weight, mut crossover_rate) = current;
+
+        if rng.random::<f32>() < JDE_TAU {
+            weight = JDE_WEIGHT_MIN + rng.random::<f32>() * (JDE_WEIGHT_MAX - JDE_WEIGHT_MIN);
+        }
+
+        if rng.random::<f32>() < JDE_TAU {
+            crossover_rate = rng.random();
+        }
+
+        (weight, crossover_rate)
+    }
+}
+
+impl Optimizer for DifferentialEvolution {
+    fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+    {
+        assert!(population.len() >= 4, "differential evolution needs at least 4 individuals");
+
+        let scored = population.iter().map(|individual| ScoredIndividual {
+            chromosome: individual.chromosome().clone(),
+            score: individual.fitness(),
+        });
+
+        // Step 1: One-to-one survivor selection
+        let mut state = match self.state.take() {
+            Some(mut state) => {
+                assert_eq!(state.targets.len(), population.len());
+
+                for (index, trial) in scored.enumerate() {
+                    if trial.score >= state.targets[index].score {
+                        state.targets[index] = trial;
+                        state.controls[index] = state.trial_controls[index];
+                    }
+                }
+
+                state
+            }
+
+            None => DeState {
+                targets: scored.collect(),
+                controls: vec![(self.weight, self.crossover_rate); population.len()],
+                trial_controls: Vec::new(),
+            },
+        };
+
+        let stats = Statistics::new(&state.targets);
+
+        // Step 2: Build a trial for every target
+        let best = fittest(&state.targets);
+
+        state.trial_controls = state
+            .controls
+            .iter()
+            .map(|&control| self.control(rng, control))
+            .collect();
+
+        let trials = (0..state.targets.len())
+            .map(|index| {
+                let control = state.trial_controls[index];
+                I::create(self.trial(rng, &state.targets, best, index, control))
+            })
+            .collect();
+
+        self.state = Some(state);
+
+        (trials, stats)
+    }
+
+    fn evolve_one<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        replacement: ReplacementPolicy,
+    ) -> (usize, I)
+    where
+        I: Individual,
+    {
+        assert!(population.len() >= 4, "differential evolution needs at least 4 individuals");
+
+        let fitness: Vec<f32> = population.iter().map(I::fitness).collect();
+        let targets = scored(population, &fitness);
+        let best = fittest(&targets);
+
+        let victim = replacement.victim(rng, population);
+        let control = self.control(rng, (self.weight, self.crossover_rate));
+
+        (victim, I::create(self.trial(rng, &targets, best, victim, control)))
+    }
+}
+
+// Index of the best target
+fn fittest(targets: &[ScoredIndividual]) -> usize {
+    (0..targets.len())
+        .max_by(|&a, &b| {
+            targets[a]
+                .score
+                .partial_cmp(&targets[b].score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .expect("got an empty population")
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::SphereIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn minimize(mut de: DifferentialEvolution) -> (DifferentialEvolution, Vec<Statistics>) {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut population: Vec<SphereIndividual> = (0..20)
+            .map(|_| {
+                let genes = (0..5).map(|_| rng.random_range(-5.0..5.0)).collect();
+                SphereIndividual::create(genes)
+            })
+            .collect();
+
+        let mut stats = Vec::new();
+
+        for _ in 0..300 {
+            let generation;
+            (population, generation) = de.evolve(&mut rng, &population);
+            stats.push(generation);
+        }
+
+        (de, stats)
+    }
+
+    #[test]
+    fn every_strategy_converges_on_a_sphere() {
+        for strategy in [DeStrategy::Rand1Bin, DeStrategy::Best1Bin, DeStrategy::CurrentToBest1] {
+            let (de, stats) = minimize(DifferentialEvolution::new(strategy, 0.5, 0.9));
+
+            assert!(stats[0].max_fitness < -10.0);
+            assert!(stats.last().unwrap().max_fitness > -1e-3, "{strategy:?}");
+
+            for gene in de.best().unwrap().0.iter() {
+                approx::assert_relative_eq!(*gene, 1.0, epsilon = 5e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn survivors_never_get_worse() {
+        let (_, stats) = minimize(DifferentialEvolution::new(DeStrategy::Rand1Bin, 0.8, 0.5));
+
+        for pair in stats.windows(2) {
+            assert!(pair[1].max_fitness >= pair[0].max_fitness);
+            assert!(pair[1].min_fitness >= pair[0].min_fitness);
+        }
+    }
+
+    #[test]
+    fn jde_adapts_its_controls() {
+        let (de, stats) = minimize(DifferentialEvolution::new(DeStrategy::Rand1Bin, 0.5, 0.9).with_jde());
+
+        assert!(stats.last().unwrap().max_fitness > -1e-4);
+        assert!(de.controls().iter().any(|&control| control != (0.5, 0.9)));
+    }
+
+    #[test]
+    fn steady_state_leaves_the_targets_alone() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut de = DifferentialEvolution::new(DeStrategy::Best1Bin, 0.5, 0.9);
+
+        let mut population: Vec<SphereIndividual> = (0..20)
+            .map(|_| {
+                let genes = (0..5).map(|_| rng.random_range(-5.0..5.0)).collect();
+                SphereIndividual::create(genes)
+            })
+            .collect();
+
+        for _ in 0..3000 {
+            let (victim, child) = de.evolve_one(&mut rng, &population, ReplacementPolicy::Worst);
+            population[victim] = child;
+        }
+
+        let best = population.iter().map(|i| i.fitness()).fold(f32::NEG_INFINITY, f32::max);
+
+        assert!(best > -1e-2);
+        assert!(de.best().is_none());
+    }
+}
+// ---------------------------------------------------------------
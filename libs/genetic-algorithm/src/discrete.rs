@@ -0,0 +1,413 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Binary genes (`bool`)
+// Flips every bit with the given chance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BitFlipMutation {
+    chance: f32,
+}
+
+// Integer genes (`i8`..`i64`, `u8`..`u32`)
+// Adds a random value from <-step, step> to every gene with the given chance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreepMutation {
+    chance: f32,
+    step: i64,
+}
+
+// Permutation genes (any gene that can be compared, e.g. `usize` indices)
+// Order crossover (OX1): keeps a slice of the first parent in place and fills
+// the rest in the order the genes appear in the second parent
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OrderCrossover;
+
+// Partially mapped crossover: keeps a slice of the first parent in place and
+// repairs the rest of the second parent through the mapping that slice defines
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PmxCrossover;
+
+// Swaps every gene with a random other one, with the given chance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwapMutation {
+    chance: f32,
+}
+
+// Reverses a random slice of the chromosome, with the given chance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InversionMutation {
+    chance: f32,
+}
+// ---------------------------------------------------------------
+
+
+// ---------------------- Gene Implementation --------------------
+// Hamming distance, `true` stands for 1.0 and anything from 0.5 up turns
+// back into `true`
+impl Gene for bool {
+    fn distance(&self, other: &Self) -> f32 {
+        if self == other { 0.0 } else { 1.0 }
+    }
+
+    fn to_real(&self) -> Option<f32> {
+        Some(if *self { 1.0 } else { 0.0 })
+    }
+
+    fn from_real(value: f32) -> Option<Self> {
+        Some(value >= 0.5)
+    }
+}
+
+impl RealGene for bool {}
+
+// Real numbers get rounded (and saturated) back into integers
+macro_rules! impl_integer_gene {
+    ($($integer:ty),*) => {
+        $(
+            impl Gene for $integer {
+                fn distance(&self, other: &Self) -> f32 {
+                    self.abs_diff(*other) as f32
+                }
+
+                fn to_real(&self) -> Option<f32> {
+                    Some(*self as f32)
+                }
+
+                fn from_real(value: f32) -> Option<Self> {
+                    Some(value.round() as $integer)
+                }
+            }
+
+            impl RealGene for $integer {}
+        )*
+    };
+}
+
+impl_integer_gene!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
+// ---------------------------------------------------------------
+
+
+// ------------------ Bit Flip Implementation --------------------
+impl BitFlipMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl MutationMethod<bool> for BitFlipMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<bool>) {
+        for gene in child.iter_mut() {
+            if rng.random_bool(self.chance as f64) {
+                *gene = !*gene;
+            }
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Creep Implementation ---------------------
+impl CreepMutation {
+    pub fn new(chance: f32, step: i64) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(step > 0);
+        Self { chance, step }
+    }
+}
+
+impl<G> MutationMethod<G> for CreepMutation
+where
+    G: Copy + Into<i64> + TryFrom<i64>,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        for gene in child.iter_mut() {
+            if rng.random_bool(self.chance as f64) {
+                let creep = rng.random_range(-self.step..=self.step);
+
+                // Genes that would overflow their type are left alone
+                if let Ok(value) = G::try_from((*gene).into().saturating_add(creep)) {
+                    *gene = value;
+                }
+            }
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ---------------- Permutation Implementation -------------------
+// Random slice `start..=end` of a chromosome with `len` genes
+fn slice(rng: &mut dyn RngCore, len: usize) -> (usize, usize) {
+    let a = rng.random_range(0..len);
+    let b = rng.random_range(0..len);
+
+    (a.min(b), a.max(b))
+}
+
+impl<G> CrossoverMethod<G> for OrderCrossover
+where
+    G: Clone + PartialEq,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let len = parent_a.len();
+        if len == 0 {
+            return Chromosome { genes: Vec::new() };
+        }
+
+        let (start, end) = slice(rng, len);
+        let kept = &parent_a.genes[start..=end];
+
+        // Genes of the second parent that aren't in the kept slice, in the
+        // order they appear right after the slice
+        let mut rest = (1..=len)
+            .map(|offset| &parent_b.genes[(end + offset) % len])
+            .filter(|gene| !kept.contains(gene));
+
+        let mut genes: Vec<Option<G>> = vec![None; len];
+
+        for (position, gene) in (start..=end).zip(kept) {
+            genes[position] = Some(gene.clone());
+        }
+
+        for offset in 1..=len - kept.len() {
+            genes[(end + offset) % len] = rest.next().cloned();
+        }
+
+        genes
+            .into_iter()
+            .map(|gene| gene.expect("parents are not permutations of each other"))
+            .collect()
+    }
+}
+
+impl<G> CrossoverMethod<G> for PmxCrossover
+where
+    G: Clone + PartialEq,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let len = parent_a.len();
+        if len == 0 {
+            return Chromosome { genes: Vec::new() };
+        }
+
+        let (start, end) = slice(rng, len);
+        let position_in_b = |gene: &G| {
+            parent_b
+                .genes
+                .iter()
+                .position(|other| other == gene)
+                .expect("parents are not permutations of each other")
+        };
+
+        let mut genes = parent_b.genes.clone();
+        genes[start..=end].clone_from_slice(&parent_a.genes[start..=end]);
+
+        // Every gene of `b` pushed out of the slice has to land somewhere
+        // outside of it, following the mapping `a[i] <-> b[i]`
+        for i in start..=end {
+            let gene = &parent_b.genes[i];
+
+            if parent_a.genes[start..=end].contains(gene) {
+                continue;
+            }
+
+            let mut position = i;
+            while (start..=end).contains(&position) {
+                position = position_in_b(&parent_a.genes[position]);
+            }
+
+            genes[position] = gene.clone();
+        }
+
+        Chromosome { genes }
+    }
+}
+
+impl SwapMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl<G> MutationMethod<G> for SwapMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        for i in 0..child.len() {
+            if rng.random_bool(self.chance as f64) {
+                let j = rng.random_range(0..child.len());
+                child.genes.swap(i, j);
+            }
+        }
+    }
+}
+
+impl InversionMutation {
+    pub fn new(chance: f32) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        Self { chance }
+    }
+}
+
+impl<G> MutationMethod<G> for InversionMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        if child.is_empty() || !rng.random_bool(self.chance as f64) {
+            return;
+        }
+
+        let (start, end) = slice(rng, child.len());
+        child.genes[start..=end].reverse();
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn genes<G: Clone>(chromosome: &Chromosome<G>) -> Vec<G> {
+        chromosome.iter().cloned().collect()
+    }
+
+    fn is_permutation(chromosome: &Chromosome<usize>) -> bool {
+        let mut genes = genes(chromosome);
+        genes.sort();
+        genes == (0..chromosome.len()).collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn bit_flip() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut child: Chromosome<bool> = vec![false; 4].into_iter().collect();
+
+        BitFlipMutation::new(1.0).mutate(&mut rng, &mut child);
+        assert_eq!(genes(&child), vec![true; 4]);
+
+        BitFlipMutation::new(0.0).mutate(&mut rng, &mut child);
+        assert_eq!(genes(&child), vec![true; 4]);
+    }
+
+    #[test]
+    fn creep_stays_within_step() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut child: Chromosome<i32> = vec![0; 100].into_iter().collect();
+
+        CreepMutation::new(1.0, 2).mutate(&mut rng, &mut child);
+
+        assert!(child.iter().all(|gene| (-2..=2).contains(gene)));
+        assert!(child.iter().any(|gene| *gene != 0));
+    }
+
+    #[test]
+    fn creep_does_not_overflow() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut child: Chromosome<u8> = vec![0, 255].into_iter().collect();
+
+        for _ in 0..100 {
+            CreepMutation::new(1.0, 5).mutate(&mut rng, &mut child);
+        }
+
+        assert_eq!(child.len(), 2);
+    }
+
+    #[test]
+    fn permutation_crossovers_keep_permutations() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let parent_a: Chromosome<usize> = (0..10).collect();
+        let parent_b: Chromosome<usize> = (0..10).rev().collect();
+
+        for _ in 0..100 {
+            assert!(is_permutation(&OrderCrossover.crossover(&mut rng, &parent_a, &parent_b)));
+            assert!(is_permutation(&PmxCrossover.crossover(&mut rng, &parent_a, &parent_b)));
+        }
+    }
+
+    #[test]
+    fn pmx_known_example() {
+        // Cut points picked by seed 42 are 1..=4
+        let mut rng = StdRng::seed_from_u64(42);
+        let (start, end) = slice(&mut rng, 8);
+        assert_eq!((start, end), (1, 4));
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let parent_a: Chromosome<usize> = vec![1, 2, 3, 4, 5, 6, 7, 8].into_iter().collect();
+        let parent_b: Chromosome<usize> = vec![3, 7, 5, 1, 6, 8, 2, 4].into_iter().collect();
+
+        let child = PmxCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+        assert_eq!(genes(&child), vec![6, 2, 3, 4, 5, 8, 7, 1]);
+    }
+
+    #[test]
+    fn permutation_mutations_keep_permutations() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut child: Chromosome<usize> = (0..10).collect();
+
+        for _ in 0..100 {
+            SwapMutation::new(0.2).mutate(&mut rng, &mut child);
+            InversionMutation::new(0.5).mutate(&mut rng, &mut child);
+
+            assert!(is_permutation(&child));
+        }
+
+        assert_ne!(genes(&child), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn evolves_permutations() {
+        // Fitness counts genes sitting at their own index
+        #[derive(Debug)]
+        struct Sorting {
+            chromosome: Chromosome<usize>,
+        }
+
+        impl Individual<usize> for Sorting {
+            fn create(chromosome: Chromosome<usize>) -> Self {
+                Self { chromosome }
+            }
+
+            fn fitness(&self) -> f32 {
+                self.chromosome.iter().enumerate().filter(|(i, g)| i == *g).count() as f32
+            }
+
+            fn chromosome(&self) -> &Chromosome<usize> {
+                &self.chromosome
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ga = GeneticAlgorithm::new(RankSelection, PmxCrossover, SwapMutation::new(0.05));
+
+        let mut population: Vec<Sorting> = (0..20)
+            .map(|_| Sorting::create((0..8).rev().collect()))
+            .collect();
+
+        for _ in 0..30 {
+            (population, _) = ga.evolve(&mut rng, &population);
+        }
+
+        assert!(population.iter().all(|individual| is_permutation(&individual.chromosome)));
+    }
+}
+// ---------------------------------------------------------------
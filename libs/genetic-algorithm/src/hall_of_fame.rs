@@ -0,0 +1,176 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Best chromosomes seen across the whole run, so that a champion isn't lost
+// just because its generation got replaced
+#[derive(Clone, Debug)]
+pub struct HallOfFame<G = f32> {
+    capacity: usize,
+    // Sorted from the best to the worst
+    members: Vec<HallOfFameEntry<G>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct HallOfFameEntry<G = f32> {
+    pub chromosome: Chromosome<G>,
+    pub fitness: f32,
+    pub generation: usize,
+}
+// ---------------------------------------------------------------
+
+
+// ----------------- Hall Of Fame Implementation -----------------
+impl<G> HallOfFame<G>
+where
+    G: Gene,
+{
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            members: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn members(&self) -> &[HallOfFameEntry<G>] {
+        &self.members
+    }
+
+    pub fn best(&self) -> Option<&HallOfFameEntry<G>> {
+        self.members.first()
+    }
+
+    // Offers every evaluated individual a place, returns how many made it in.
+    // Chromosomes already in the hall are not added twice, nor are the ones
+    // with a non-finite fitness (which can't be ranked against the others).
+    pub fn update<I>(&mut self, population: &[I], generation: usize) -> usize
+    where
+        I: Individual<G>,
+    {
+        let mut inserted = 0;
+
+        for individual in population {
+            let fitness = individual.fitness();
+
+            if !fitness.is_finite() {
+                continue;
+            }
+
+            let full = self.members.len() >= self.capacity;
+            if full && self.members.last().is_some_and(|worst| worst.fitness >= fitness) {
+                continue;
+            }
+
+            let known = self
+                .members
+                .iter()
+                .any(|member| member.chromosome.distance(individual.chromosome()) == 0.0);
+
+            if known {
+                continue;
+            }
+
+            let position = self.members.partition_point(|member| member.fitness >= fitness);
+
+            self.members.insert(position, HallOfFameEntry {
+                chromosome: individual.chromosome().clone(),
+                fitness,
+                generation,
+            });
+
+            self.members.truncate(self.capacity);
+            inserted += 1;
+        }
+
+        inserted
+    }
+
+    // Replaces the first `count` individuals of a (freshly bred) population
+    // with the best members of the hall
+    pub fn inject<I>(&self, population: &mut [I], count: usize)
+    where
+        I: Individual<G>,
+    {
+        for (individual, member) in population.iter_mut().zip(&self.members).take(count) {
+            *individual = I::create(member.chromosome.clone());
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().cloned().collect())
+    }
+
+    fn fitnesses(hall: &HallOfFame) -> Vec<(f32, usize)> {
+        hall.members()
+            .iter()
+            .map(|member| (member.fitness, member.generation))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_the_best_across_generations() {
+        let mut hall = HallOfFame::new(3);
+
+        hall.update(&[individual(&[1.0]), individual(&[5.0]), individual(&[3.0])], 0);
+        hall.update(&[individual(&[4.0]), individual(&[0.0])], 1);
+
+        assert_eq!(fitnesses(&hall), vec![(5.0, 0), (4.0, 1), (3.0, 0)]);
+    }
+
+    #[test]
+    fn skips_duplicates() {
+        let mut hall = HallOfFame::new(3);
+
+        let inserted = hall.update(&[individual(&[2.0, 2.0]), individual(&[2.0, 2.0])], 0);
+
+        assert_eq!(inserted, 1);
+        assert_eq!(hall.len(), 1);
+    }
+
+    #[test]
+    fn skips_non_finite_fitness() {
+        let mut hall = HallOfFame::new(3);
+
+        let inserted = hall.update(
+            &[individual(&[f32::NAN]), individual(&[2.0]), individual(&[f32::INFINITY])],
+            0,
+        );
+
+        assert_eq!(inserted, 1);
+        assert_eq!(fitnesses(&hall), vec![(2.0, 0)]);
+    }
+
+    #[test]
+    fn injects_the_best_members() {
+        let mut hall = HallOfFame::new(3);
+        hall.update(&[individual(&[1.0]), individual(&[7.0]), individual(&[3.0])], 0);
+
+        let mut population = vec![individual(&[0.0]), individual(&[0.0]), individual(&[0.0])];
+        hall.inject(&mut population, 2);
+
+        assert_eq!(population, vec![individual(&[7.0]), individual(&[3.0]), individual(&[0.0])]);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,319 @@
+use crate::*;
+
+use rand::seq::SliceRandom;
+use rand_distr::{
+    Distribution,
+    Normal,
+};
+use std::{
+    fs,
+    path::Path,
+};
+
+// ----------------------- Definitions ---------------------------
+// Where the first generation comes from, e.g. to warm-start a new
+// experiment from the results of an old one
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Initializer {
+    // Every gene drawn from <min, max>
+    Uniform { min: f32, max: f32 },
+    // Latin hypercube sampling: <min, max> is split into as many intervals as
+    // there are individuals and every interval of every gene is used exactly
+    // once, which covers the space more evenly than `Uniform`
+    LatinHypercube { min: f32, max: f32 },
+    // The seed itself, followed by copies of it with gaussian noise (of the
+    // given standard deviation) added to every gene
+    Perturbation { seed: Chromosome, sigma: f32 },
+    // Saved chromosomes, repeated as many times as needed
+    Population(Vec<Chromosome>),
+}
+
+// What `Initializer::load` accepts
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedChromosomes {
+    Checkpoint {
+        version: u32,
+        population: Vec<EvaluatedChromosome>,
+    },
+    Population(Vec<Chromosome>),
+    Champion(Chromosome),
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Initializer Implementation ---------------
+impl Initializer {
+    // Loads a JSON file holding a checkpoint (see `Checkpoint`), a list of
+    // chromosomes or a single one (e.g. a hall of fame champion).
+    //
+    // Binary checkpoints have to be loaded through `Checkpoint::load` (their
+    // parameters can't be skipped over), see `Initializer::from_checkpoint`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError> {
+        let bytes = fs::read(path)?;
+
+        let json = std::str::from_utf8(&bytes).map_err(|_| CheckpointError::NotACheckpoint)?;
+
+        let chromosomes = match serde_json::from_str(json)? {
+            SavedChromosomes::Checkpoint { version, population } => {
+                if version != CHECKPOINT_VERSION {
+                    return Err(CheckpointError::UnsupportedVersion {
+                        found: version,
+                        supported: CHECKPOINT_VERSION,
+                    });
+                }
+
+                population.into_iter().map(|entry| entry.chromosome).collect()
+            }
+
+            SavedChromosomes::Population(chromosomes) => chromosomes,
+            SavedChromosomes::Champion(chromosome) => vec![chromosome],
+        };
+
+        Ok(Initializer::Population(chromosomes))
+    }
+
+    // Writes chromosomes in the format `load` reads back
+    pub fn save(path: impl AsRef<Path>, chromosomes: &[Chromosome]) -> Result<(), CheckpointError> {
+        write_atomically(path.as_ref(), serde_json::to_string(chromosomes)?.as_bytes())
+    }
+
+    pub fn from_checkpoint<P>(checkpoint: &Checkpoint<P>) -> Self {
+        Initializer::Population(
+            checkpoint
+                .population
+                .iter()
+                .map(|entry| entry.chromosome.clone())
+                .collect(),
+        )
+    }
+
+    // Fails when the saved chromosomes (or the seed) don't have `genes` genes
+    // or when the parameters don't make sense (e.g. an empty range)
+    pub fn chromosomes(
+        &self,
+        rng: &mut dyn RngCore,
+        size: usize,
+        genes: usize,
+    ) -> Result<Vec<Chromosome>, CheckpointError> {
+        let chromosomes = match self {
+            Initializer::Uniform { min, max } => {
+                check_range(*min, *max)?;
+
+                (0..size)
+                    .map(|_| (0..genes).map(|_| rng.random_range(*min..=*max)).collect())
+                    .collect()
+            }
+
+            Initializer::LatinHypercube { min, max } => {
+                check_range(*min, *max)?;
+
+                let width = (max - min) / size as f32;
+                let mut individuals = vec![Vec::with_capacity(genes); size];
+
+                for _ in 0..genes {
+                    let mut intervals: Vec<usize> = (0..size).collect();
+                    intervals.shuffle(rng);
+
+                    for (individual, interval) in individuals.iter_mut().zip(intervals) {
+                        individual.push(min + (interval as f32 + rng.random::<f32>()) * width);
+                    }
+                }
+
+                individuals.into_iter().map(Chromosome::from_iter).collect()
+            }
+
+            Initializer::Perturbation { seed, sigma } => {
+                check_genes(seed, genes)?;
+
+                let noise = Normal::new(0.0, *sigma)
+                    .ok()
+                    .filter(|_| *sigma >= 0.0)
+                    .ok_or_else(|| {
+                        CheckpointError::InvalidInitializer(format!("got an invalid sigma ({sigma})"))
+                    })?;
+
+                (0..size)
+                    .map(|index| {
+                        let mut chromosome = seed.clone();
+
+                        if index > 0 {
+                            for gene in chromosome.iter_mut() {
+                                *gene += noise.sample(rng);
+                            }
+                        }
+
+                        chromosome
+                    })
+                    .collect()
+            }
+
+            Initializer::Population(chromosomes) => {
+                if chromosomes.is_empty() {
+                    return Err(CheckpointError::EmptyPopulation);
+                }
+
+                for chromosome in chromosomes {
+                    check_genes(chromosome, genes)?;
+                }
+
+                chromosomes.iter().cycle().take(size).cloned().collect()
+            }
+        };
+
+        Ok(chromosomes)
+    }
+
+    pub fn population<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        size: usize,
+        genes: usize,
+    ) -> Result<Vec<I>, CheckpointError>
+    where
+        I: Individual,
+    {
+        let chromosomes = self.chromosomes(rng, size, genes)?;
+        Ok(chromosomes.into_iter().map(I::create).collect())
+    }
+}
+
+fn check_range(min: f32, max: f32) -> Result<(), CheckpointError> {
+    if min.is_finite() && max.is_finite() && min < max {
+        Ok(())
+    } else {
+        Err(CheckpointError::InvalidInitializer(format!("got an invalid range ({min}..{max})")))
+    }
+}
+
+fn check_genes(chromosome: &Chromosome, expected: usize) -> Result<(), CheckpointError> {
+    if chromosome.len() == expected {
+        Ok(())
+    } else {
+        Err(CheckpointError::GeneCount { found: chromosome.len(), expected })
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    #[test]
+    fn latin_hypercube_uses_every_interval_once() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let chromosomes =
+            Initializer::LatinHypercube { min: -1.0, max: 1.0 }.chromosomes(&mut rng, 8, 3).unwrap();
+
+        assert_eq!(chromosomes.len(), 8);
+
+        for gene in 0..3 {
+            let mut intervals: Vec<usize> = chromosomes
+                .iter()
+                .map(|chromosome| ((chromosome[gene] + 1.0) / 0.25) as usize)
+                .collect();
+
+            intervals.sort();
+            assert_eq!(intervals, (0..8).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn perturbation_stays_around_the_seed() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let seed: Chromosome = vec![1.0, -2.0, 3.0].into_iter().collect();
+
+        let initializer = Initializer::Perturbation { seed: seed.clone(), sigma: 0.1 };
+        let population: Vec<TestIndividual> = initializer.population(&mut rng, 5, 3).unwrap();
+
+        assert_eq!(population[0].chromosome(), &seed);
+        assert!(population[1..].iter().all(|individual| individual.chromosome() != &seed));
+        assert!(population.iter().all(|individual| individual.chromosome().distance(&seed) < 1.0));
+    }
+
+    #[test]
+    fn loads_checkpoints_populations_and_champions() {
+        let directory = std::env::temp_dir().join(format!("initializer-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let rng = rand_chacha::ChaCha8Rng::seed_from_u64(42);
+        let saved: Vec<TestIndividual> = [[1.0, 2.0], [3.0, 4.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+            .collect();
+
+        let checkpoint = directory.join("checkpoint.json");
+        Checkpoint::new(7, &saved, (), &rng).save(&checkpoint, CheckpointFormat::Json).unwrap();
+
+        let population = directory.join("population.json");
+        let chromosomes: Vec<Chromosome> = saved.iter().map(|i| i.chromosome().clone()).collect();
+        Initializer::save(&population, &chromosomes).unwrap();
+
+        let champion = directory.join("champion.json");
+        fs::write(&champion, "[5.0, 6.0]").unwrap();
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut genes = |path: &Path| -> Vec<Vec<f32>> {
+            Initializer::load(path)
+                .unwrap()
+                .chromosomes(&mut rng, 3, 2)
+                .unwrap()
+                .iter()
+                .map(|chromosome| chromosome.iter().cloned().collect())
+                .collect()
+        };
+
+        let expected = [vec![1.0, 2.0], vec![3.0, 4.0], vec![1.0, 2.0]];
+        assert_eq!(genes(&checkpoint), expected);
+        assert_eq!(genes(&population), expected);
+        assert_eq!(genes(&champion), vec![vec![5.0, 6.0]; 3]);
+
+        fs::write(&champion, "{\"champion\": true}").unwrap();
+        assert!(matches!(Initializer::load(&champion), Err(CheckpointError::Json(_))));
+
+        assert!(matches!(
+            Initializer::load(&population).unwrap().chromosomes(&mut rng, 3, 4),
+            Err(CheckpointError::GeneCount { found: 2, expected: 4 })
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn rejects_an_empty_population() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        assert!(matches!(
+            Initializer::Population(Vec::new()).chromosomes(&mut rng, 3, 2),
+            Err(CheckpointError::EmptyPopulation)
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_parameters() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let seed: Chromosome = vec![0.0; 2].into_iter().collect();
+
+        for initializer in [
+            Initializer::Uniform { min: 1.0, max: 1.0 },
+            Initializer::Uniform { min: 0.0, max: f32::INFINITY },
+            Initializer::LatinHypercube { min: 1.0, max: -1.0 },
+            Initializer::LatinHypercube { min: f32::NAN, max: 1.0 },
+            Initializer::Perturbation { seed: seed.clone(), sigma: -1.0 },
+            Initializer::Perturbation { seed, sigma: f32::NAN },
+        ] {
+            assert!(matches!(
+                initializer.chromosomes(&mut rng, 3, 2),
+                Err(CheckpointError::InvalidInitializer(_))
+            ));
+        }
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,387 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Island model: several sub-populations evolved side by side, each with its
+// own `GeneticAlgorithm`, exchanging their individuals every few generations
+#[derive(Clone, Debug)]
+pub struct IslandModel<S, C, M> {
+    islands: Vec<GeneticAlgorithm<S, C, M>>,
+    migration: Migration,
+    generation: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Migration {
+    interval: usize,
+    count: usize,
+    topology: Topology,
+    selection: MigrantSelection,
+    replacement: MigrantReplacement,
+}
+
+// Which islands send migrants to which
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Topology {
+    // Island `i` sends to island `i + 1`, the last one sends to the first
+    Ring,
+    // Every island sends to every other island
+    FullyConnected,
+    // Every island sends to one other island picked at random
+    Random,
+}
+
+// Which individuals leave an island
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrantSelection {
+    Best,
+    Random,
+}
+
+// Which individuals make room for the incoming migrants
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrantReplacement {
+    Worst,
+    Random,
+}
+
+#[derive(Clone, Debug)]
+pub struct IslandStatistics {
+    pub islands: Vec<Statistics>,
+    pub global: Statistics,
+    pub migrated: bool,
+}
+// ---------------------------------------------------------------
+
+
+// ------------------ Migration Implementation -------------------
+impl Migration {
+    pub fn new(interval: usize, count: usize) -> Self {
+        assert!(interval > 0);
+
+        Self {
+            interval,
+            count,
+            topology: Topology::Ring,
+            selection: MigrantSelection::Best,
+            replacement: MigrantReplacement::Worst,
+        }
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn with_selection(mut self, selection: MigrantSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn with_replacement(mut self, replacement: MigrantReplacement) -> Self {
+        self.replacement = replacement;
+        self
+    }
+
+    // (source, destination) pairs for a single migration
+    fn routes(&self, rng: &mut dyn RngCore, islands: usize) -> Vec<(usize, usize)> {
+        if islands < 2 {
+            return Vec::new();
+        }
+
+        match self.topology {
+            Topology::Ring => (0..islands).map(|from| (from, (from + 1) % islands)).collect(),
+
+            Topology::FullyConnected => (0..islands)
+                .flat_map(|from| {
+                    (0..islands)
+                        .filter(move |&to| to != from)
+                        .map(move |to| (from, to))
+                })
+                .collect(),
+
+            Topology::Random => (0..islands)
+                .map(|from| {
+                    // Pick from everyone but ourselves
+                    let to = rng.random_range(0..islands - 1);
+                    (from, if to >= from { to + 1 } else { to })
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn migrate<I, G>(&self, rng: &mut dyn RngCore, islands: &mut [Vec<I>])
+    where
+        I: Individual<G> + Clone,
+    {
+        let routes = self.routes(rng, islands.len());
+
+        // Migrants are picked before anyone arrives, so the order of routes
+        // doesn't matter
+        let mut incoming: Vec<Vec<I>> = vec![Vec::new(); islands.len()];
+
+        for (from, to) in routes {
+            let source = &islands[from];
+            let count = self.count.min(source.len());
+
+            let migrants = match self.selection {
+                MigrantSelection::Best => ranked(source).into_iter().rev().take(count).collect(),
+                MigrantSelection::Random => random(rng, source, count),
+            };
+
+            incoming[to].extend(migrants.into_iter().map(|index| source[index].clone()));
+        }
+
+        for (island, migrants) in islands.iter_mut().zip(incoming) {
+            let count = migrants.len().min(island.len());
+
+            let replaced = match self.replacement {
+                MigrantReplacement::Worst => ranked(island).into_iter().take(count).collect(),
+                MigrantReplacement::Random => random(rng, island, count),
+            };
+
+            for (index, migrant) in replaced.into_iter().zip(migrants) {
+                island[index] = migrant;
+            }
+        }
+    }
+}
+
+// Indices of the population, sorted from the worst to the best
+fn ranked<I, G>(population: &[I]) -> Vec<usize>
+where
+    I: Individual<G>,
+{
+    let mut indices: Vec<usize> = (0..population.len()).collect();
+
+    indices.sort_by(|&a, &b| {
+        population[a]
+            .fitness()
+            .partial_cmp(&population[b].fitness())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    indices
+}
+
+fn random<I>(rng: &mut dyn RngCore, population: &[I], count: usize) -> Vec<usize> {
+    (0..population.len())
+        .collect::<Vec<_>>()
+        .choose_multiple(rng, count)
+        .cloned()
+        .collect()
+}
+// ---------------------------------------------------------------
+
+
+// ----------------- Island Model Implementation -----------------
+impl<S, C, M> IslandModel<S, C, M>
+where
+    S: SelectionMethod,
+{
+    // Every island gets its own GA, so operators (e.g. mutation rates)
+    // can differ between islands
+    pub fn new(islands: Vec<GeneticAlgorithm<S, C, M>>, migration: Migration) -> Self {
+        assert!(!islands.is_empty());
+
+        Self {
+            islands,
+            migration,
+            generation: 0,
+        }
+    }
+
+    pub fn islands(&self) -> &[GeneticAlgorithm<S, C, M>] {
+        &self.islands
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn evolve<I, G>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        mut populations: Vec<Vec<I>>,
+    ) -> (Vec<Vec<I>>, IslandStatistics)
+    where
+        I: Individual<G> + Clone,
+        G: Gene,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+    {
+        assert_eq!(populations.len(), self.islands.len());
+
+        self.generation += 1;
+
+        // Step 1: Exchange individuals while their fitness is still known
+        let migrated = self.generation.is_multiple_of(self.migration.interval);
+
+        if migrated {
+            self.migration.migrate(rng, &mut populations);
+        }
+
+        // Step 2: Evolve every island on its own
+        let (populations, islands): (Vec<_>, Vec<_>) = self
+            .islands
+            .iter_mut()
+            .zip(&populations)
+            .map(|(ga, population)| Optimizer::evolve(ga, rng, population))
+            .unzip();
+
+        let global = Statistics::combine(&islands, &populations);
+
+        (populations, IslandStatistics { islands, global, migrated })
+    }
+}
+
+impl Statistics {
+    // Merges per-island statistics, weighting averages by island size
+    fn combine<I>(islands: &[Statistics], populations: &[Vec<I>]) -> Self {
+        let total: usize = populations.iter().map(Vec::len).sum();
+
+        let sum_fitness: f32 = islands
+            .iter()
+            .zip(populations)
+            .map(|(stats, population)| stats.avg_fitness * population.len() as f32)
+            .sum();
+
+        Self {
+            min_fitness: islands.iter().map(|s| s.min_fitness).fold(f32::INFINITY, f32::min),
+            max_fitness: islands.iter().map(|s| s.max_fitness).fold(f32::NEG_INFINITY, f32::max),
+            avg_fitness: sum_fitness / total as f32,
+            species: Vec::new(),
+            mutation: None,
+            restart: None,
+            local_search: None,
+            operators: None,
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn fitnesses(population: &[TestIndividual]) -> Vec<i32> {
+        population.iter().map(|i| i.fitness() as i32).collect()
+    }
+
+    #[test]
+    fn ring_sends_best_and_replaces_worst() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut islands = vec![
+            vec![TestIndividual::new(1.0), TestIndividual::new(9.0), TestIndividual::new(5.0)],
+            vec![TestIndividual::new(2.0), TestIndividual::new(3.0), TestIndividual::new(4.0)],
+        ];
+
+        Migration::new(1, 1).migrate(&mut rng, &mut islands);
+
+        assert_eq!(fitnesses(&islands[0]), vec![4, 9, 5]);
+        assert_eq!(fitnesses(&islands[1]), vec![9, 3, 4]);
+    }
+
+    #[test]
+    fn fully_connected_reaches_every_island() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut islands = vec![
+            vec![TestIndividual::new(10.0), TestIndividual::new(0.0), TestIndividual::new(0.0)],
+            vec![TestIndividual::new(20.0), TestIndividual::new(0.0), TestIndividual::new(0.0)],
+            vec![TestIndividual::new(30.0), TestIndividual::new(0.0), TestIndividual::new(0.0)],
+        ];
+
+        Migration::new(1, 1)
+            .with_topology(Topology::FullyConnected)
+            .migrate(&mut rng, &mut islands);
+
+        let mut first = fitnesses(&islands[0]);
+        first.sort();
+
+        assert_eq!(first, vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn random_topology_never_sends_to_itself() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let migration = Migration::new(1, 1).with_topology(Topology::Random);
+
+        for _ in 0..100 {
+            for (from, to) in migration.routes(&mut rng, 4) {
+                assert_ne!(from, to);
+            }
+        }
+    }
+
+    #[test]
+    fn evolve_migrates_on_interval() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let mut model = IslandModel::new(vec![ga.clone(), ga], Migration::new(2, 1));
+
+        let individual = |genes: &[f32]| TestIndividual::create(genes.iter().cloned().collect());
+
+        let mut populations = vec![
+            vec![individual(&[0.0, 0.0]), individual(&[1.0, 1.0])],
+            vec![individual(&[2.0, 2.0]), individual(&[3.0, 3.0])],
+        ];
+
+        let mut migrated = Vec::new();
+
+        for _ in 0..4 {
+            let (next, stats) = model.evolve(&mut rng, populations);
+
+            assert_eq!(stats.islands.len(), 2);
+            assert!(stats.global.min_fitness <= stats.global.avg_fitness);
+            assert!(stats.global.avg_fitness <= stats.global.max_fitness);
+
+            migrated.push(stats.migrated);
+            populations = next;
+        }
+
+        assert_eq!(migrated, vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn islands_adapt_their_mutation() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let min = MutationParameters { chance: 0.0, coeff: 0.0 };
+        let ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            AdaptiveGaussianMutation::new(0.5, 1.0, MutationControl::Decay { rate: 0.5, min }),
+        );
+
+        let mut model = IslandModel::new(vec![ga.clone(), ga], Migration::new(10, 1));
+
+        let mut populations: Vec<Vec<TestIndividual>> = (0..2)
+            .map(|_| (0..4).map(|i| TestIndividual::create(vec![i as f32; 2].into_iter().collect())).collect())
+            .collect();
+
+        let mut coeffs = Vec::new();
+
+        for _ in 0..3 {
+            let stats;
+            (populations, stats) = model.evolve(&mut rng, populations);
+            coeffs.push(stats.islands[0].mutation.unwrap().coeff);
+        }
+
+        assert_eq!(coeffs, [1.0, 0.5, 0.25]);
+    }
+}
+// ---------------------------------------------------------------
@@ -1,73 +1,274 @@
 use rand::{
     Rng,
     RngCore,
+    SeedableRng,
     seq::IndexedRandom
 };
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 use std::{
     ops::Index,
 };
 
+mod adaptive;
+mod checkpoint;
+mod cmaes;
+mod coevolution;
+mod constraints;
+mod crowding;
+mod differential_evolution;
+mod discrete;
+mod hall_of_fame;
+mod initializer;
+mod island;
+mod lineage;
+mod local_search;
+mod map_elites;
+mod novelty;
+mod nsga2;
+mod observer;
+mod operator_selection;
+mod registry;
+mod restart;
+mod runner;
+mod scaling;
+mod speciation;
+mod steady_state;
+
+pub use crate::{
+    adaptive::*,
+    checkpoint::*,
+    cmaes::*,
+    coevolution::*,
+    constraints::*,
+    crowding::*,
+    differential_evolution::*,
+    discrete::*,
+    hall_of_fame::*,
+    initializer::*,
+    island::*,
+    lineage::*,
+    local_search::*,
+    map_elites::*,
+    novelty::*,
+    nsga2::*,
+    observer::*,
+    operator_selection::*,
+    registry::*,
+    restart::*,
+    runner::*,
+    scaling::*,
+    speciation::*,
+    steady_state::*,
+};
+
 // ----------------------- Definitions ---------------------------
+// Anything that turns an evaluated population into the next one, so that
+// callers can switch between the GA and other optimizers (e.g. `CmaEs`)
+pub trait Optimizer<G = f32> {
+    fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual<G>;
+
+    // Steady-state step, see `GeneticAlgorithm::evolve_one`.
+    //
+    // By default a whole new population is created and only its first child
+    // is kept, optimizers that can do better should override this. The ones
+    // that expect their own children back (e.g. `CmaEs`) have to.
+    fn evolve_one<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        replacement: ReplacementPolicy,
+    ) -> (usize, I)
+    where
+        I: Individual<G>,
+    {
+        let (children, _) = self.evolve(rng, population);
+        let victim = replacement.victim(rng, population);
+
+        (victim, children.into_iter().next().expect("got an empty population"))
+    }
+}
+
 // Genetic Algorithm
-#[derive(Clone, Debug)]
-pub struct GeneticAlgorithm<S, C, M> {
+// `Ob` watches every step of the evolution, see `Observer`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GeneticAlgorithm<S, C, M, Ob = ()> {
     selection_method: S,
     crossover_method: C,
-    mutation_method: M
+    mutation_method: M,
+    speciation: Option<Speciation>,
+    // Clones among the children, see `DuplicateFilter`
+    duplicates: Option<DuplicateFilter>,
+    restarts: Option<Restarts>,
+    local_search: Option<LocalSearch>,
+    // Which operator of a portfolio breeds every child, see `OperatorSelection`
+    operator_selection: Option<OperatorSelection>,
+    // Observers aren't part of a checkpoint, a restored GA gets a fresh one
+    #[serde(skip)]
+    observer: Ob,
+    // Feedback for adaptive mutation, see `MutationMethod::adapt`
+    adaptation: Adaptation,
+    // Children bred one by one since the current round started, see
+    // `GeneticAlgorithm::evolve_one`
+    #[serde(default)]
+    births: usize,
 }
 
-#[derive(Clone, Debug)]
+// GA whose operators are picked at runtime, e.g. through a `Registry`
+pub type DynGeneticAlgorithm<G = f32> =
+    GeneticAlgorithm<DynSelectionMethod, DynCrossoverMethod<G>, DynMutationMethod<G>>;
+
+pub type DynSelectionMethod = Box<dyn SelectionMethod + Send + Sync>;
+pub type DynCrossoverMethod<G = f32> = Box<dyn CrossoverMethod<G> + Send + Sync>;
+pub type DynMutationMethod<G = f32> = Box<dyn MutationMethod<G> + Send + Sync>;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Statistics {
+    #[serde(with = "non_finite")]
     pub min_fitness: f32,
+    #[serde(with = "non_finite")]
     pub max_fitness: f32,
+    #[serde(with = "non_finite")]
     pub avg_fitness: f32,
+    // Empty unless the GA was built with speciation
+    pub species: Vec<SpeciesStatistics>,
+    // Mutation parameters the next generation was bred with, if the
+    // mutation method reports them
+    pub mutation: Option<MutationParameters>,
+    // Set when the next generation was restarted, see `Restarts`
+    pub restart: Option<RestartEvent>,
+    // How the local search went on the next generation, see `LocalSearch`
+    pub local_search: Option<LocalSearchStatistics>,
+    // How the operators of a portfolio were picked for the next generation,
+    // see `OperatorSelection`
+    pub operators: Option<OperatorStatistics>,
 }
 
 // Individual
-#[derive(Clone, Debug)]
-pub struct Chromosome {
-    genes: Vec<f32>,
+// Genes default to `f32` weights, see `Gene` for the other kinds
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Chromosome<G = f32> {
+    genes: Vec<G>,
 }
 
-pub trait Individual {
-    fn create(chromosome: Chromosome) -> Self;
+// Anything a chromosome can be made of
+pub trait Gene: Clone {
+    // How far apart two genes are, see `Chromosome::distance`
+    fn distance(&self, other: &Self) -> f32;
+
+    // Genes that stand in for real numbers convert to and from `f32`, which
+    // is what restarts, local search and replacing duplicates work with (see
+    // `RealGene`)
+    fn to_real(&self) -> Option<f32> {
+        None
+    }
+
+    fn from_real(_value: f32) -> Option<Self> {
+        None
+    }
+}
+
+// Genes whose `to_real` and `from_real` never return `None`; the GA only
+// takes restarts, local search and duplicate replacement for these
+pub trait RealGene: Gene {}
+
+pub trait Individual<G = f32> {
+    fn create(chromosome: Chromosome<G>) -> Self;
     fn fitness(&self) -> f32;
-    fn chromosome(&self) -> &Chromosome;
+    fn chromosome(&self) -> &Chromosome<G>;
+}
+
+// Chromosome paired with an already computed score, so that the regular
+// operators can be run on something other than the raw fitness
+#[derive(Clone, Debug)]
+pub(crate) struct ScoredIndividual<G = f32> {
+    pub(crate) chromosome: Chromosome<G>,
+    pub(crate) score: f32,
 }
 
 
 // Selection Method
+// Only `select_index` has to be implemented, which keeps the trait usable as
+// `dyn SelectionMethod` (see `DynGeneticAlgorithm`)
 pub trait SelectionMethod {
-    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    // Index of the selected individual, given the fitness of everyone
+    fn select_index(&self, rng: &mut dyn RngCore, fitness: &[f32]) -> usize;
+
+    fn select<'a, I, G>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
-        I: Individual;
+        I: Individual<G>,
+        Self: Sized,
+    {
+        let fitness: Vec<f32> = population.iter().map(I::fitness).collect();
+        &population[self.select_index(rng, &fitness)]
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RankSelection;
 
 
 // Crossover Method
-pub trait CrossoverMethod {
+pub trait CrossoverMethod<G = f32> {
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
-        parent_a: &Chromosome,
-        parent_b: &Chromosome,
-    ) -> Chromosome;
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G>;
+
+    // Portfolios hold more than one operator, see `CrossoverPortfolio`
+    fn operators(&self) -> usize {
+        1
+    }
+
+    // Crossover with the given operator of the portfolio
+    fn crossover_with(
+        &self,
+        rng: &mut dyn RngCore,
+        _operator: usize,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        self.crossover(rng, parent_a, parent_b)
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct UniformCrossover;
 
 
 // Mutation Method
-pub trait MutationMethod {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+pub trait MutationMethod<G = f32> {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>);
+
+    // Called by `Optimizer::evolve` before every generation is bred, see
+    // `AdaptiveGaussianMutation`
+    fn adapt(&mut self, _feedback: &MutationFeedback) {}
+
+    fn parameters(&self) -> Option<MutationParameters> {
+        None
+    }
+
+    // Portfolios hold more than one operator, see `MutationPortfolio`
+    fn operators(&self) -> usize {
+        1
+    }
+
+    // Mutation with the given operator of the portfolio
+    fn mutate_with(&self, rng: &mut dyn RngCore, _operator: usize, child: &mut Chromosome<G>) {
+        self.mutate(rng, child)
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GaussianMutation {
     chance: f32,
     coeff: f32,
@@ -77,21 +278,18 @@ pub struct GaussianMutation {
 
 // --------------- Rank Selection Impementation ------------------
 impl SelectionMethod for RankSelection {
-    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
-    where
-        I: Individual,
-    {
+    fn select_index(&self, rng: &mut dyn RngCore, fitness: &[f32]) -> usize {
         // Sort population by fitness to determine rank
-        let mut sorted_population: Vec<&I> = population.iter().collect();
-        sorted_population.sort_by(|a, b| {
-            a.fitness()
-                .partial_cmp(&b.fitness())
+        let mut sorted_population: Vec<usize> = (0..fitness.len()).collect();
+        sorted_population.sort_by(|&a, &b| {
+            fitness[a]
+                .partial_cmp(&fitness[b])
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
         // Zip the sorted individuals with their rank (weight)
         // Rank starts at 1 for the lowest fitness
-        let ranked_population: Vec<(&I, usize)> = sorted_population
+        let ranked_population: Vec<(usize, usize)> = sorted_population
             .into_iter()
             .enumerate()
             .map(|(index, individual)| (individual, index + 1))
@@ -109,20 +307,88 @@ impl SelectionMethod for RankSelection {
 // ---------------------------------------------------------------
 
 
+// ------------------ Boxed Operators Impementation --------------
+impl<S> SelectionMethod for Box<S>
+where
+    S: SelectionMethod + ?Sized,
+{
+    fn select_index(&self, rng: &mut dyn RngCore, fitness: &[f32]) -> usize {
+        (**self).select_index(rng, fitness)
+    }
+}
+
+impl<G, C> CrossoverMethod<G> for Box<C>
+where
+    C: CrossoverMethod<G> + ?Sized,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        (**self).crossover(rng, parent_a, parent_b)
+    }
+
+    fn operators(&self) -> usize {
+        (**self).operators()
+    }
+
+    fn crossover_with(
+        &self,
+        rng: &mut dyn RngCore,
+        operator: usize,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        (**self).crossover_with(rng, operator, parent_a, parent_b)
+    }
+}
+
+impl<G, M> MutationMethod<G> for Box<M>
+where
+    M: MutationMethod<G> + ?Sized,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        (**self).mutate(rng, child)
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        (**self).adapt(feedback)
+    }
+
+    fn parameters(&self) -> Option<MutationParameters> {
+        (**self).parameters()
+    }
+
+    fn operators(&self) -> usize {
+        (**self).operators()
+    }
+
+    fn mutate_with(&self, rng: &mut dyn RngCore, operator: usize, child: &mut Chromosome<G>) {
+        (**self).mutate_with(rng, operator, child)
+    }
+}
+// ---------------------------------------------------------------
+
+
 // ------------------ Crossover Impementation --------------------
-impl CrossoverMethod for UniformCrossover {
+impl<G> CrossoverMethod<G> for UniformCrossover
+where
+    G: Clone,
+{
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
-        parent_a: &Chromosome,
-        parent_b: &Chromosome,
-    ) -> Chromosome {
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
         assert_eq!(parent_a.len(), parent_b.len());
 
         parent_a
             .iter()
             .zip(parent_b.iter())
-            .map(|(&a, &b)| if rng.random_bool(0.5) { a } else { b })
+            .map(|(a, b)| if rng.random_bool(0.5) { a.clone() } else { b.clone() })
             .collect()
     }
 }
@@ -132,7 +398,7 @@ impl CrossoverMethod for UniformCrossover {
 // ------------------- Mutation Impementation --------------------
 impl GaussianMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
         Self { chance, coeff }
     }
 }
@@ -147,15 +413,22 @@ impl MutationMethod for GaussianMutation {
             }
         }
     }
+
+    fn parameters(&self) -> Option<MutationParameters> {
+        Some(MutationParameters {
+            chance: self.chance,
+            coeff: self.coeff,
+        })
+    }
 }
 // ---------------------------------------------------------------
 
 
 // ----------------- Statistics Impementation --------------------
 impl Statistics {
-    fn new<I>(population: &[I]) -> Self
+    pub fn new<I, G>(population: &[I]) -> Self
     where
-        I: Individual,
+        I: Individual<G>,
     {
         assert!(!population.is_empty());
 
@@ -175,17 +448,79 @@ impl Statistics {
             min_fitness,
             max_fitness,
             avg_fitness: sum_fitness / (population.len() as f32),
+            species: Vec::new(),
+            mutation: None,
+            restart: None,
+            local_search: None,
+            operators: None,
         }
     }
 }
 // ---------------------------------------------------------------
 
 
+// ------------- Scored Individual Impementation -----------------
+impl<G> Individual<G> for ScoredIndividual<G> {
+    fn create(chromosome: Chromosome<G>) -> Self {
+        Self { chromosome, score: 0.0 }
+    }
+
+    fn fitness(&self) -> f32 {
+        self.score
+    }
+
+    fn chromosome(&self) -> &Chromosome<G> {
+        &self.chromosome
+    }
+}
+// ---------------------------------------------------------------
+
+
+// Population with `scores` in place of its fitness
+pub(crate) fn scored<I, G>(population: &[I], scores: &[f32]) -> Vec<ScoredIndividual<G>>
+where
+    I: Individual<G>,
+    G: Clone,
+{
+    assert_eq!(population.len(), scores.len());
+
+    population
+        .iter()
+        .zip(scores)
+        .map(|(individual, &score)| ScoredIndividual {
+            chromosome: individual.chromosome().clone(),
+            score,
+        })
+        .collect()
+}
+
+// Lets `optimizer` breed the next generation as if `scores` were the
+// population's fitness
+pub(crate) fn evolve_scored<O, I>(
+    rng: &mut dyn RngCore,
+    optimizer: &mut O,
+    population: &[I],
+    scores: &[f32],
+) -> Vec<I>
+where
+    O: Optimizer,
+    I: Individual,
+{
+    let (children, _) = optimizer.evolve(rng, &scored(population, scores));
+
+    children
+        .into_iter()
+        .map(|child| I::create(child.chromosome))
+        .collect()
+}
+// ---------------------------------------------------------------
+
+
 // ----------------- Chromosome Impementation --------------------
 // Just make chromosomes a vector 👍
 // Enable indexing for chromosomes
-impl Index<usize> for Chromosome {
-    type Output = f32;
+impl<G> Index<usize> for Chromosome<G> {
+    type Output = G;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.genes[index]
@@ -193,8 +528,8 @@ impl Index<usize> for Chromosome {
 }
 
 // Enable iterating Chromosomes
-impl FromIterator<f32> for Chromosome {
-    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+impl<G> FromIterator<G> for Chromosome<G> {
+    fn from_iter<T: IntoIterator<Item = G>>(iter: T) -> Self {
         Self {
             genes: iter.into_iter().collect(),
         }
@@ -202,27 +537,82 @@ impl FromIterator<f32> for Chromosome {
 }
 
 // Implement the iterator trait for Chromosome
-impl IntoIterator for Chromosome {
-    type Item = f32;
-    type IntoIter = std::vec::IntoIter<f32>;
+impl<G> IntoIterator for Chromosome<G> {
+    type Item = G;
+    type IntoIter = std::vec::IntoIter<G>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.genes.into_iter()
     }
 }
 
-impl Chromosome {
+impl Gene for f32 {
+    fn distance(&self, other: &Self) -> f32 {
+        (self - other).abs()
+    }
+
+    fn to_real(&self) -> Option<f32> {
+        Some(*self)
+    }
+
+    fn from_real(value: f32) -> Option<Self> {
+        Some(value)
+    }
+}
+
+impl RealGene for f32 {}
+
+impl<G> Chromosome<G> {
     pub fn len(&self) -> usize {
         self.genes.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &f32> {
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &G> {
         self.genes.iter()
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut G> {
         self.genes.iter_mut()
     }
+
+    // Copy of a chromosome made of real-valued genes, see `Gene::to_real`
+    pub(crate) fn to_real(&self) -> Chromosome
+    where
+        G: Gene,
+    {
+        self.iter()
+            .map(|gene| gene.to_real().expect("only real-valued genes are supported here"))
+            .collect()
+    }
+
+    pub(crate) fn from_real(chromosome: Chromosome) -> Self
+    where
+        G: Gene,
+    {
+        chromosome
+            .into_iter()
+            .map(|gene| G::from_real(gene).expect("only real-valued genes are supported here"))
+            .collect()
+    }
+
+    // Euclidean distance between two chromosomes of the same length, built
+    // from the distances between their genes
+    pub fn distance(&self, other: &Chromosome<G>) -> f32
+    where
+        G: Gene,
+    {
+        assert_eq!(self.len(), other.len());
+
+        self.iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.distance(b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
 }
 // ---------------------------------------------------------------
 
@@ -231,44 +621,480 @@ impl Chromosome {
 impl<S, C, M> GeneticAlgorithm<S, C, M>
 where
     S: SelectionMethod,
-    C: CrossoverMethod,
-    M: MutationMethod
 {
     
     pub fn new(selection_method: S, crossover_method: C, mutation_method: M) -> Self {
         Self { 
             selection_method,
             crossover_method,
-            mutation_method
+            mutation_method,
+            speciation: None,
+            duplicates: None,
+            restarts: None,
+            local_search: None,
+            operator_selection: None,
+            observer: (),
+            adaptation: Adaptation::default(),
+            births: 0,
+        }
+    }
+}
+
+// Crossover and mutation are bound per method, so a single GA type works
+// with any kind of genes its operators support
+impl<S, C, M, Ob> GeneticAlgorithm<S, C, M, Ob>
+where
+    S: SelectionMethod,
+{
+    // Replaces the current observer (if any)
+    pub fn with_observer<Ob2>(self, observer: Ob2) -> GeneticAlgorithm<S, C, M, Ob2> {
+        GeneticAlgorithm {
+            selection_method: self.selection_method,
+            crossover_method: self.crossover_method,
+            mutation_method: self.mutation_method,
+            speciation: self.speciation,
+            duplicates: self.duplicates,
+            restarts: self.restarts,
+            local_search: self.local_search,
+            operator_selection: self.operator_selection,
+            observer,
+            adaptation: self.adaptation,
+            births: self.births,
         }
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    pub fn observer(&self) -> &Ob {
+        &self.observer
+    }
+
+    // Copies every optional setting of `other` (speciation, duplicate filter,
+    // restarts, local search and operator selection), e.g. when only the
+    // operators should change; both have to work on the same kind of genes
+    pub fn with_options_of<S2, C2, M2, Ob2, G>(mut self, other: &GeneticAlgorithm<S2, C2, M2, Ob2>) -> Self
+    where
+        M: MutationMethod<G>,
+        M2: MutationMethod<G>,
+    {
+        self.speciation = other.speciation.clone();
+        self.duplicates = other.duplicates;
+        self.restarts = other.restarts.clone();
+        self.local_search = other.local_search.clone();
+        self.operator_selection = other.operator_selection.clone();
+        self
+    }
+
+    // Breed every species separately, see `Speciation`
+    pub fn with_speciation(mut self, speciation: Speciation) -> Self {
+        self.speciation = Some(speciation);
+        self
+    }
+
+    // Re-mutates or replaces children that are clones of their parents or
+    // of each other
+    pub fn with_duplicate_filter<G>(mut self, duplicates: DuplicateFilter) -> Self
+    where
+        G: RealGene,
+        M: MutationMethod<G>,
+    {
+        self.duplicates = Some(duplicates);
+        self
+    }
+
+    pub fn duplicate_filter(&self) -> Option<&DuplicateFilter> {
+        self.duplicates.as_ref()
+    }
+
+    // Restarts the search when it gets stuck, see `Restarts`; needs whole
+    // generations, so it doesn't work with `evolve_one`
+    pub fn with_restarts<G>(mut self, restarts: Restarts) -> Self
+    where
+        G: RealGene,
+        M: MutationMethod<G>,
+    {
+        self.restarts = Some(restarts);
+        self
+    }
+
+    pub fn restarts(&self) -> Option<&Restarts> {
+        self.restarts.as_ref()
+    }
+
+    // Improves every child with a few steps of local search, see
+    // `LocalSearch`; `evolve_one` only supports the Lamarckian one
+    pub fn with_local_search<G>(mut self, local_search: LocalSearch) -> Self
+    where
+        G: RealGene,
+        M: MutationMethod<G>,
+    {
+        self.local_search = Some(local_search);
+        self
+    }
+
+    pub fn local_search(&self) -> Option<&LocalSearch> {
+        self.local_search.as_ref()
+    }
+
+    // Picks between the operators of a `CrossoverPortfolio` and/or a
+    // `MutationPortfolio` by how well their children do
+    pub fn with_operator_selection(mut self, operator_selection: OperatorSelection) -> Self {
+        self.operator_selection = Some(operator_selection);
+        self
+    }
+
+    pub fn operator_selection(&self) -> Option<&OperatorSelection> {
+        self.operator_selection.as_ref()
+    }
+
+    // Breeds the next generation, telling which parents every child came from
+    fn evolve_tracked<I, G>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+    ) -> (Vec<I>, Vec<[usize; 2]>, Statistics)
     where
-        I: Individual,
+        I: Individual<G>,
+        G: Gene,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+        Ob: Observer<G>,
     {
         // if population is empty, who you gonna mutate?
         assert!(!population.is_empty());
 
-        let new_population = (0..population.len())
-        .map(|_| {
-            // 1. Selection
-            let parent_a = self.selection_method.select(rng, population).chromosome();
-            let parent_b = self.selection_method.select(rng, population).chromosome();
+        self.observer.generation_start(population.len());
 
-            // 2. Crossover
-            let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        let (species, mut stats) = self.speciate(population);
+        stats.mutation = self.mutation_method.parameters();
 
-            // TODO mutation
-            self.mutation_method.mutate(rng, &mut child);
+        let (new_population, parents) = Self::pools(&species, population.len())
+            .into_iter()
+            .enumerate()
+            .map(|(child, pool)| {
+                let (chromosome, parents) = self.breed_from(rng, population, &species, child, pool);
+                (I::create(chromosome), parents)
+            })
+            .unzip();
 
-            I::create(child)
-        })
-        .collect();
-    
-        let stats = Statistics::new(population);
+        self.observer.generation_end(&stats);
 
-        (new_population, stats)
+        (new_population, parents, stats)
+    }
+
+    // Same as `Optimizer::evolve`, but every child is bred with its own RNG
+    // stream derived from `seed`, so the result doesn't depend on the order (or
+    // the thread) in which children are bred. With the `parallel` feature
+    // they're bred on rayon (and the observer is called from multiple threads,
+    // in no particular order).
+    pub fn evolve_seeded<I, G>(&mut self, seed: u64, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual<G> + Send + Sync,
+        G: Gene + Send + Sync,
+        S: Sync,
+        C: CrossoverMethod<G> + Sync,
+        M: MutationMethod<G> + Sync,
+        Ob: Observer<G> + Sync,
+    {
+        // Everything but the breeding itself gets a stream of its own
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_stream(u64::MAX);
+
+        let (children, parents, stats) = match self.prepare(&mut rng, population) {
+            Some(learned) => unlearned(population, self.breed_seeded(seed, &scored(population, &learned))),
+            None => self.breed_seeded(seed, population),
+        };
+
+        self.finish(&mut rng, population, children, &parents, stats)
+    }
+
+    fn breed_seeded<I, G>(&self, seed: u64, population: &[I]) -> (Vec<I>, Vec<[usize; 2]>, Statistics)
+    where
+        I: Individual<G> + Send + Sync,
+        G: Gene + Send + Sync,
+        S: Sync,
+        C: CrossoverMethod<G> + Sync,
+        M: MutationMethod<G> + Sync,
+        Ob: Observer<G> + Sync,
+    {
+        self.observer.generation_start(population.len());
+
+        let (species, mut stats) = self.speciate(population);
+        stats.mutation = self.mutation_method.parameters();
+
+        let pools = Self::pools(&species, population.len());
+
+        let breed = |(child, pool): (usize, Option<usize>)| {
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            rng.set_stream(child as u64);
+
+            let (chromosome, parents) = self.breed_from(&mut rng, population, &species, child, pool);
+            (I::create(chromosome), parents)
+        };
+
+        #[cfg(feature = "parallel")]
+        let (new_population, parents) = pools.into_par_iter().enumerate().map(breed).unzip();
+
+        #[cfg(not(feature = "parallel"))]
+        let (new_population, parents) = pools.into_iter().enumerate().map(breed).unzip();
+
+        self.observer.generation_end(&stats);
+
+        (new_population, parents, stats)
+    }
+
+    // Everything that happens before a generation is bred: the mutation
+    // method adapts to how the previous generations went and the operators of
+    // a portfolio get credited and picked. Returns the fitness the population
+    // has learned, if the local search is Baldwinian.
+    fn prepare<I, G>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> Option<Vec<f32>>
+    where
+        I: Individual<G>,
+        G: Gene,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+    {
+        assert!(!population.is_empty());
+
+        let feedback = self.adaptation.feedback(population);
+        self.mutation_method.adapt(&feedback);
+
+        if let Some(operator_selection) = &mut self.operator_selection {
+            operator_selection.credit(
+                self.adaptation.successes(population).as_deref(),
+                self.crossover_method.operators(),
+                self.mutation_method.operators(),
+            );
+
+            operator_selection.choose(rng, population.len());
+        }
+
+        self.local_search
+            .as_mut()
+            .and_then(|local_search| local_search.take_learned(population))
+    }
+
+    // Everything that happens once a generation is bred: duplicates get
+    // filtered out, the search restarts when it gets stuck and the local
+    // search runs
+    fn finish<I, G>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        mut children: Vec<I>,
+        parents: &[[usize; 2]],
+        mut stats: Statistics,
+    ) -> (Vec<I>, Statistics)
+    where
+        I: Individual<G>,
+        G: Gene,
+        M: MutationMethod<G>,
+        Ob: Observer<G>,
+    {
+        self.adaptation.bred(population, parents);
+
+        if let Some(operator_selection) = &self.operator_selection {
+            stats.operators = Some(operator_selection.statistics());
+        }
+
+        if let Some(duplicates) = &self.duplicates {
+            let replaced = duplicates.filter(rng, &self.mutation_method, population, &mut children);
+
+            if let Some(operator_selection) = &mut self.operator_selection {
+                operator_selection.replaced(&replaced);
+            }
+        }
+
+        if let Some(restarts) = &mut self.restarts {
+            stats.restart = restarts.apply(rng, population, &stats, &mut children);
+
+            if stats.restart.is_some() {
+                self.adaptation.restarted();
+                self.observer.population_replaced(children.len());
+            }
+        }
+
+        if let Some(local_search) = &mut self.local_search {
+            let (local_search, rewritten) = local_search.apply(rng, &mut children);
+            stats.local_search = Some(local_search);
+
+            if let Some(operator_selection) = &mut self.operator_selection {
+                operator_selection.replaced(&rewritten);
+            }
+        }
+
+        (children, stats)
+    }
+
+    fn speciate<I, G>(&self, population: &[I]) -> (Vec<Species<G>>, Statistics)
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        let mut stats = Statistics::new(population);
+
+        let species = match &self.speciation {
+            Some(speciation) => speciation.speciate(population),
+            None => Vec::new(),
+        };
+
+        stats.species = species.iter().map(SpeciesStatistics::new).collect();
+
+        (species, stats)
+    }
+
+    // Which species every child is bred from, `None` meaning the whole population
+    fn pools<G>(species: &[Species<G>], size: usize) -> Vec<Option<usize>> {
+        if species.is_empty() {
+            return vec![None; size];
+        }
+
+        species
+            .iter()
+            .enumerate()
+            .flat_map(|(index, niche)| std::iter::repeat_n(Some(index), niche.offspring))
+            .collect()
+    }
+
+    fn breed_from<I, G>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        species: &[Species<G>],
+        child: usize,
+        pool: Option<usize>,
+    ) -> (Chromosome<G>, [usize; 2])
+    where
+        I: Individual<G>,
+        G: Clone,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+        Ob: Observer<G>,
+    {
+        match pool {
+            Some(index) => {
+                let members = &species[index].members;
+                self.breed(rng, members, child, |parent| members[parent].index)
+            }
+            None => self.breed(rng, population, child, |parent| parent),
+        }
+    }
+
+    // `origin` maps an index within `parents` to the one within the whole
+    // population, which is what the observer (and the caller) gets to see
+    pub(crate) fn breed<I, G>(
+        &self,
+        rng: &mut dyn RngCore,
+        parents: &[I],
+        child: usize,
+        origin: impl Fn(usize) -> usize,
+    ) -> (Chromosome<G>, [usize; 2])
+    where
+        I: Individual<G>,
+        G: Clone,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+        Ob: Observer<G>,
+    {
+        let fitness: Vec<f32> = parents.iter().map(I::fitness).collect();
+
+        // 1. Selection
+        let index_a = self.selection_method.select_index(rng, &fitness);
+        let parent_a = parents[index_a].chromosome();
+        let origin_a = origin(index_a);
+        self.observer.parent_selected(child, origin_a, parent_a);
+
+        let index_b = self.selection_method.select_index(rng, &fitness);
+        let parent_b = parents[index_b].chromosome();
+        let origin_b = origin(index_b);
+        self.observer.parent_selected(child, origin_b, parent_b);
+
+        let operators = self
+            .operator_selection
+            .as_ref()
+            .and_then(|operator_selection| operator_selection.choice(child));
+
+        // 2. Crossover
+        let mut offspring = match operators {
+            Some([crossover, _]) => {
+                self.crossover_method.crossover_with(rng, crossover, parent_a, parent_b)
+            }
+            None => self.crossover_method.crossover(rng, parent_a, parent_b),
+        };
+        self.observer.crossover_applied(child, parent_a, parent_b, &offspring);
+
+        // 3. Mutation
+        let mutate = |rng: &mut dyn RngCore, offspring: &mut Chromosome<G>| match operators {
+            Some([_, mutation]) => self.mutation_method.mutate_with(rng, mutation, offspring),
+            None => self.mutation_method.mutate(rng, offspring),
+        };
+
+        if Ob::ENABLED {
+            let before = offspring.clone();
+            mutate(rng, &mut offspring);
+            self.observer.mutation_applied(child, &before, &offspring);
+        } else {
+            mutate(rng, &mut offspring);
+        }
+
+        self.observer.child_created(child, &offspring);
+
+        (offspring, [origin_a, origin_b])
+    }
+}
+
+// Baldwinian learning: parents are selected on what they've learned, but
+// the statistics report the raw fitness
+fn unlearned<I, G>(
+    population: &[I],
+    (children, parents, learned): (Vec<ScoredIndividual<G>>, Vec<[usize; 2]>, Statistics),
+) -> (Vec<I>, Vec<[usize; 2]>, Statistics)
+where
+    I: Individual<G>,
+{
+    let stats = Statistics {
+        species: learned.species,
+        mutation: learned.mutation,
+        ..Statistics::new(population)
+    };
+
+    let children = children.into_iter().map(|child| I::create(child.chromosome)).collect();
+
+    (children, parents, stats)
+}
+
+impl<S, C, M, Ob, G> Optimizer<G> for GeneticAlgorithm<S, C, M, Ob>
+where
+    S: SelectionMethod,
+    G: Gene,
+    C: CrossoverMethod<G>,
+    M: MutationMethod<G>,
+    Ob: Observer<G>,
+{
+    // Besides breeding, lets the mutation method adapt to how the previous
+    // generations went, credits the operators of a portfolio, filters out
+    // duplicates, restarts the search when it gets stuck and runs the local
+    // search (whichever of those the GA was built with)
+    fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual<G>,
+    {
+        let (children, parents, stats) = match self.prepare(rng, population) {
+            Some(learned) => unlearned(population, self.evolve_tracked(rng, &scored(population, &learned))),
+            None => self.evolve_tracked(rng, population),
+        };
+
+        self.finish(rng, population, children, &parents, stats)
+    }
+
+    fn evolve_one<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        replacement: ReplacementPolicy,
+    ) -> (usize, I)
+    where
+        I: Individual<G>,
+    {
+        GeneticAlgorithm::evolve_one(self, rng, population, replacement)
     }
 }
 // ---------------------------------------------------------------
@@ -286,13 +1112,13 @@ mod tests {
     use std::iter::FromIterator;
     
     #[derive(Clone, Debug, PartialEq)]
-    enum TestIndividual {
+    pub(crate) enum TestIndividual {
         WithChromosome { chromosome: Chromosome },
         WithFitness { fitness: f32 },
     }
 
     impl TestIndividual {
-        fn new(fitness: f32) -> Self {
+        pub(crate) fn new(fitness: f32) -> Self {
             Self::WithFitness { fitness }
         }
     }
@@ -329,6 +1155,144 @@ mod tests {
         }
     }
 
+    // Behaves exactly like its genes
+    impl BehavioralIndividual for TestIndividual {
+        fn behaviour(&self) -> Vec<f32> {
+            self.chromosome().iter().cloned().collect()
+        }
+    }
+
+    // Negated sphere function centered at 1.0, so the best fitness is 0.0
+    #[derive(Clone, Debug)]
+    pub(crate) struct SphereIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Individual for SphereIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+
+        fn fitness(&self) -> f32 {
+            -self.chromosome.iter().map(|gene| (gene - 1.0).powi(2)).sum::<f32>()
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+    }
+
+    // Two equally high peaks at (-5, -5) and (5, 5)
+    #[derive(Clone, Debug)]
+    pub(crate) struct TwoPeaksIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Individual for TwoPeaksIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+
+        fn fitness(&self) -> f32 {
+            let distance = |peak: f32| self.chromosome.iter().map(|x| (x - peak).powi(2)).sum::<f32>();
+            -distance(-5.0).min(distance(5.0))
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+    }
+
+    // Behaviour = first two genes, fitness = the third one
+    #[derive(Clone, Debug)]
+    pub(crate) struct GridIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Individual for GridIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+
+        fn fitness(&self) -> f32 {
+            self.chromosome[2]
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+    }
+
+    impl BehavioralIndividual for GridIndividual {
+        fn behaviour(&self) -> Vec<f32> {
+            vec![self.chromosome[0], self.chromosome[1]]
+        }
+    }
+
+    // Objectives are the genes themselves
+    #[derive(Clone, Debug)]
+    pub(crate) struct ParetoIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl MultiObjectiveIndividual for ParetoIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+
+        fn objectives(&self) -> Vec<f32> {
+            self.chromosome.iter().cloned().collect()
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+    }
+
+    // Fitness is the sum of genes, the first gene must not be positive
+    #[derive(Clone, Debug)]
+    pub(crate) struct Constrained(TestIndividual);
+
+    impl Individual for Constrained {
+        fn create(chromosome: Chromosome) -> Self {
+            Self(TestIndividual::create(chromosome))
+        }
+
+        fn fitness(&self) -> f32 {
+            self.0.fitness()
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            self.0.chromosome()
+        }
+    }
+
+    impl ConstrainedIndividual for Constrained {
+        fn violation(&self) -> f32 {
+            self.chromosome()[0].max(0.0)
+        }
+    }
+
+    // Fitness is the number of set bits
+    #[derive(Clone, Debug)]
+    pub(crate) struct BitIndividual {
+        chromosome: Chromosome<bool>,
+    }
+
+    impl Individual<bool> for BitIndividual {
+        fn create(chromosome: Chromosome<bool>) -> Self {
+            Self { chromosome }
+        }
+
+        fn fitness(&self) -> f32 {
+            self.chromosome.iter().filter(|&&bit| bit).count() as f32
+        }
+
+        fn chromosome(&self) -> &Chromosome<bool> {
+            &self.chromosome
+        }
+    }
+
     #[test]
     fn genetic_algorithm() {
         fn individual(genes: &[f32]) -> TestIndividual {
@@ -337,7 +1301,7 @@ mod tests {
 
         let mut rng = StdRng::seed_from_u64(42);
 
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RankSelection,
             UniformCrossover,
             GaussianMutation::new(0.5, 0.5),
@@ -365,6 +1329,49 @@ mod tests {
         assert_eq!(population, expected_population);
     }
 
+    mod evolve_seeded {
+        use super::*;
+
+        fn population() -> Vec<TestIndividual> {
+            [[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [1.0, 2.0, 1.0], [1.0, 2.0, 4.0]]
+                .iter()
+                .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+                .collect()
+        }
+
+        fn ga() -> GeneticAlgorithm<RankSelection, UniformCrossover, GaussianMutation> {
+            GeneticAlgorithm::new(
+                RankSelection,
+                UniformCrossover,
+                GaussianMutation::new(0.5, 0.5),
+            )
+        }
+
+        #[test]
+        fn is_reproducible() {
+            let (a, _) = ga().evolve_seeded(42, &population());
+            let (b, _) = ga().evolve_seeded(42, &population());
+            let (c, _) = ga().evolve_seeded(43, &population());
+
+            assert_eq!(a, b);
+            assert_ne!(a, c);
+        }
+
+        #[cfg(feature = "parallel")]
+        #[test]
+        fn does_not_depend_on_thread_count() {
+            let evolve = |threads: usize| {
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .unwrap()
+                    .install(|| ga().evolve_seeded(42, &population()).0)
+            };
+
+            assert_eq!(evolve(1), evolve(4));
+        }
+    }
+
     #[test]
     fn ranked_selection() {
         let mut rng = StdRng::seed_from_u64(42);
@@ -521,7 +1528,7 @@ mod tests {
                 #[test]
                 fn entirely_changes_the_original_chromosome() {
                     let actual = actual(0.5);
-                    let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+                    let expected = [1.0, 2.0, 3.0, 4.0, 5.0];
 
                     for (a, e) in actual.iter().zip(expected.iter()) {
                         assert_ne!(a, e);
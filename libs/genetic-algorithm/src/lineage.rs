@@ -0,0 +1,361 @@
+use crate::*;
+
+use std::{
+    collections::BTreeSet,
+    fmt::Write,
+    sync::{Mutex, MutexGuard},
+};
+
+// ----------------------- Definitions ---------------------------
+// Observer that gives every individual a stable ID and remembers who its
+// parents were, see `LineageStore` for walking the result.
+//
+// IDs follow the population order: the population handed to `evolve` has to
+// be the one the previous `evolve` returned (in the same order). The first
+// population seen becomes generation 0, without any parents. A population
+// that got replaced (e.g. by a restart) or changed its size starts over
+// without parents as well. Steady-state births (`evolve_one`) are not
+// recorded.
+#[derive(Debug, Default)]
+pub struct LineageRecorder {
+    store: Mutex<LineageStore>,
+}
+
+// Every individual recorded so far, indexed by ID. Nothing is ever dropped,
+// so long runs with big populations take a fair bit of memory.
+#[derive(Clone, Debug, Default)]
+pub struct LineageStore {
+    records: Vec<LineageRecord>,
+    // IDs of the current population, by index
+    current: Vec<usize>,
+    // Parents picked so far for every child of the generation being bred
+    pending: Vec<Vec<usize>>,
+    generation: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageRecord {
+    pub id: usize,
+    pub generation: usize,
+    // In the order they were selected, empty for generation 0
+    pub parents: Vec<usize>,
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Recorder Implementation ------------------
+impl LineageRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn store(&self) -> MutexGuard<'_, LineageStore> {
+        self.store.lock().expect("lineage store got poisoned")
+    }
+
+    pub fn into_store(self) -> LineageStore {
+        self.store.into_inner().expect("lineage store got poisoned")
+    }
+}
+
+impl Clone for LineageRecorder {
+    fn clone(&self) -> Self {
+        Self {
+            store: Mutex::new(self.store().clone()),
+        }
+    }
+}
+
+impl<G> Observer<G> for LineageRecorder {
+    // Only parents are recorded, chromosomes don't have to be copied
+    const ENABLED: bool = false;
+
+    fn generation_start(&self, population: usize) {
+        let mut store = self.store();
+
+        if store.current.len() != population {
+            let generation = store.generation;
+            store.current = (0..population).map(|_| store.push(generation, Vec::new())).collect();
+        }
+
+        store.pending = vec![Vec::new(); population];
+    }
+
+    fn parent_selected(&self, child: usize, parent: usize, _chromosome: &Chromosome<G>) {
+        let mut store = self.store();
+        let parent = store.current[parent];
+
+        if let Some(parents) = store.pending.get_mut(child) {
+            parents.push(parent);
+        }
+    }
+
+    fn generation_end(&self, _stats: &Statistics) {
+        let mut store = self.store();
+        let generation = store.generation + 1;
+        let pending = std::mem::take(&mut store.pending);
+
+        store.current = pending
+            .into_iter()
+            .map(|parents| store.push(generation, parents))
+            .collect();
+
+        store.generation = generation;
+    }
+
+    fn population_replaced(&self, _population: usize) {
+        self.store().current.clear();
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ---------------------- Store Implementation -------------------
+impl LineageStore {
+    pub fn records(&self) -> &[LineageRecord] {
+        &self.records
+    }
+
+    pub fn record(&self, id: usize) -> Option<&LineageRecord> {
+        self.records.get(id)
+    }
+
+    // IDs of the current population, e.g. `current()[best]` is the champion
+    pub fn current(&self) -> &[usize] {
+        &self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Every ancestor (through both parents) back to generation 0, the most
+    // recent ones first
+    pub fn ancestors(&self, id: usize) -> Vec<usize> {
+        let mut ancestors = BTreeSet::new();
+        let mut queue = vec![id];
+
+        while let Some(id) = queue.pop() {
+            for &parent in &self.records[id].parents {
+                if ancestors.insert(parent) {
+                    queue.push(parent);
+                }
+            }
+        }
+
+        ancestors.into_iter().rev().collect()
+    }
+
+    // Every descendant (through either parent), the oldest ones first
+    pub fn descendants(&self, id: usize) -> Vec<usize> {
+        let mut descendants = BTreeSet::from([id]);
+
+        // Children always get higher IDs than their parents
+        for record in &self.records[id + 1..] {
+            if record.parents.iter().any(|parent| descendants.contains(parent)) {
+                descendants.insert(record.id);
+            }
+        }
+
+        descendants.remove(&id);
+        descendants.into_iter().collect()
+    }
+
+    // Phylogenetic tree in the Newick format, e.g. `((2,3)0,(4)1);`.
+    //
+    // A tree allows only a single parent, so every individual hangs under its
+    // first parent; generation 0 sits under an unnamed root.
+    pub fn to_newick(&self) -> String {
+        self.newick(|_| true)
+    }
+
+    // Same as `to_newick`, but only with the (first parent) ancestry of the
+    // given individuals - e.g. how the current champion came to be
+    pub fn to_newick_of(&self, ids: &[usize]) -> String {
+        let mut kept = BTreeSet::new();
+
+        for &id in ids {
+            let mut id = Some(id);
+
+            while let Some(current) = id.filter(|&id| kept.insert(id)) {
+                id = self.records[current].parents.first().copied();
+            }
+        }
+
+        self.newick(|id| kept.contains(&id))
+    }
+
+    // `[{"id": 0, "generation": 0, "parents": []}, ...]`
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.records).expect("lineage records are always valid JSON")
+    }
+
+    fn push(&mut self, generation: usize, parents: Vec<usize>) -> usize {
+        let id = self.records.len();
+        self.records.push(LineageRecord { id, generation, parents });
+        id
+    }
+
+    fn newick(&self, keep: impl Fn(usize) -> bool) -> String {
+        let mut children = vec![Vec::new(); self.records.len()];
+        let mut roots = Vec::new();
+
+        for record in self.records.iter().filter(|record| keep(record.id)) {
+            match record.parents.first() {
+                Some(&parent) => children[parent].push(record.id),
+                None => roots.push(record.id),
+            }
+        }
+
+        let mut newick = String::new();
+        write_subtrees(&mut newick, &children, &roots);
+        newick.push(';');
+        newick
+    }
+}
+
+// Lineages can be thousands of generations deep, so the tree is walked with
+// an explicit stack instead of recursion
+fn write_subtrees(newick: &mut String, children: &[Vec<usize>], roots: &[usize]) {
+    // Siblings being written, with how many of them have been started
+    let mut stack = vec![(roots, 0)];
+    newick.push('(');
+
+    while let Some((ids, started)) = stack.last_mut() {
+        let Some(&id) = ids.get(*started) else {
+            stack.pop();
+            newick.push(')');
+
+            // Subtree is closed, the individual it hangs under comes next
+            if let Some((ids, started)) = stack.last() {
+                write!(newick, "{}", ids[started - 1]).unwrap();
+            }
+
+            continue;
+        };
+
+        if *started > 0 {
+            newick.push(',');
+        }
+
+        *started += 1;
+
+        if children[id].is_empty() {
+            write!(newick, "{id}").unwrap();
+        } else {
+            newick.push('(');
+            stack.push((&children[id], 0));
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Generation 0: 0, 1
+    // Generation 1: 2 (0 x 1), 3 (1 x 1)
+    // Generation 2: 4 (3 x 2), 5 (3 x 3)
+    fn recorder() -> LineageRecorder {
+        let recorder = LineageRecorder::new();
+        let stats = Statistics::new(&[crate::tests::TestIndividual::new(0.0)]);
+
+        for parents in [[(0, 1), (1, 1)], [(1, 0), (1, 1)]] {
+            Observer::<f32>::generation_start(&recorder, 2);
+
+            for (child, (a, b)) in parents.into_iter().enumerate() {
+                let chromosome: Chromosome = Chromosome { genes: Vec::new() };
+                recorder.parent_selected(child, a, &chromosome);
+                recorder.parent_selected(child, b, &chromosome);
+            }
+
+            Observer::<f32>::generation_end(&recorder, &stats);
+        }
+
+        recorder
+    }
+
+    #[test]
+    fn records_parents() {
+        let store = recorder().into_store();
+
+        assert_eq!(store.current(), [4, 5]);
+        assert_eq!(store.generation(), 2);
+        assert_eq!(
+            store.record(4),
+            Some(&LineageRecord { id: 4, generation: 2, parents: vec![3, 2] })
+        );
+    }
+
+    #[test]
+    fn walks_ancestors_and_descendants() {
+        let store = recorder().into_store();
+
+        assert_eq!(store.ancestors(4), [3, 2, 1, 0]);
+        assert_eq!(store.ancestors(5), [3, 1]);
+        assert_eq!(store.descendants(0), [2, 4]);
+        assert_eq!(store.descendants(1), [2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn exports_newick_and_json() {
+        let store = recorder().into_store();
+
+        assert_eq!(store.to_newick(), "((2)0,((4,5)3)1);");
+        assert_eq!(store.to_newick_of(&[4]), "(((4)3)1);");
+        assert_eq!(store.to_newick_of(&[]), "();");
+        assert!(store.to_json().starts_with(
+            "[{\"id\":0,\"generation\":0,\"parents\":[]},\
+              {\"id\":1,\"generation\":0,\"parents\":[]},\
+              {\"id\":2,\"generation\":1,\"parents\":[0,1]}"
+        ));
+
+        let records: Vec<LineageRecord> = serde_json::from_str(&store.to_json()).unwrap();
+        assert_eq!(records, store.records());
+    }
+
+    #[test]
+    fn exports_deep_lineages() {
+        let recorder = LineageRecorder::new();
+        let stats = Statistics::new(&[crate::tests::TestIndividual::new(0.0)]);
+        let chromosome: Chromosome = Chromosome { genes: Vec::new() };
+
+        for _ in 0..100_000 {
+            Observer::<f32>::generation_start(&recorder, 1);
+            recorder.parent_selected(0, 0, &chromosome);
+            Observer::<f32>::generation_end(&recorder, &stats);
+        }
+
+        let newick = recorder.store().to_newick();
+
+        assert!(newick.starts_with(&format!("{}100000)99999)", "(".repeat(100_001))));
+        assert!(newick.ends_with(")1)0);"));
+    }
+
+    #[test]
+    fn tracks_a_real_evolution() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.5, 0.5))
+            .with_observer(LineageRecorder::new());
+
+        let mut population: Vec<_> = (0..4)
+            .map(|i| crate::tests::TestIndividual::create(vec![i as f32].into_iter().collect()))
+            .collect();
+
+        for _ in 0..3 {
+            (population, _) = ga.evolve(&mut rng, &population);
+        }
+
+        let store = ga.observer().store();
+
+        assert_eq!(store.records().len(), 4 * 4);
+        assert!(store.records().iter().skip(4).all(|record| record.parents.len() == 2));
+        assert!(store.ancestors(store.current()[0]).iter().any(|&id| id < 4));
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,379 @@
+use crate::*;
+
+use rand_distr::{
+    Distribution,
+    Normal,
+};
+
+// ----------------------- Definitions ---------------------------
+// Memetic hook: children get a few steps of local search before they're
+// handed back, see `GeneticAlgorithm::with_local_search`.
+//
+// Candidates are evaluated through `I::create(chromosome).fitness()`, so
+// this only makes sense for individuals whose fitness follows from their
+// chromosome alone (and doesn't need e.g. a whole simulation to be run).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LocalSearch {
+    method: LocalSearchMethod,
+    // Fitness evaluations every searched child gets
+    budget: usize,
+    // Chance of a child being searched at all
+    probability: f32,
+    learning: Learning,
+    // Gaussian noise of `step` standard deviation, for hill climbing
+    noise: Normal<f32>,
+    // Children handed out last time, with the fitness they have learned
+    // (Baldwinian)
+    learned: Vec<(Chromosome, f32)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LocalSearchMethod {
+    // Gaussian noise of `step` standard deviation is added to every gene,
+    // the candidate is kept if it's better
+    HillClimbing { step: f32 },
+    // Every gene in turn is moved by +step and -step, the step is halved once
+    // a whole sweep brings no improvement
+    CoordinateSearch { step: f32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Learning {
+    // Improvements are written back into the chromosome
+    Lamarckian,
+    // Chromosome stays as it was, only the fitness it has learned is used
+    // when the child gets selected as a parent in the next generation
+    Baldwinian,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LocalSearchStatistics {
+    pub searched: usize,
+    pub improved: usize,
+    pub evaluations: usize,
+    // Average fitness gained by the searched children
+    #[serde(with = "non_finite")]
+    pub avg_gain: f32,
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Local Search Implementation --------------
+impl LocalSearch {
+    pub fn new(
+        method: LocalSearchMethod,
+        budget: usize,
+        probability: f32,
+        learning: Learning,
+    ) -> Self {
+        let (LocalSearchMethod::HillClimbing { step }
+        | LocalSearchMethod::CoordinateSearch { step }) = method;
+
+        assert!(step > 0.0);
+        assert!(budget > 0);
+        assert!((0.0..=1.0).contains(&probability));
+
+        Self {
+            method,
+            budget,
+            probability,
+            learning,
+            noise: Normal::new(0.0, step).expect("got an invalid step"),
+            learned: Vec::new(),
+        }
+    }
+
+    pub fn learning(&self) -> Learning {
+        self.learning
+    }
+
+    // Baldwinian fitness of `population`, if it's the one handed out last
+    // (same chromosomes in the same order)
+    pub(crate) fn take_learned<I, G>(&mut self, population: &[I]) -> Option<Vec<f32>>
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        let learned = std::mem::take(&mut self.learned);
+
+        let handed_out = learned.len() == population.len()
+            && learned
+                .iter()
+                .zip(population)
+                .all(|((chromosome, _), individual)| {
+                    individual.chromosome().to_real().distance(chromosome) == 0.0
+                });
+
+        handed_out.then(|| learned.into_iter().map(|(_, fitness)| fitness).collect())
+    }
+
+    // Also returns the children whose genes got rewritten (Lamarckian)
+    pub(crate) fn apply<I, G>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        children: &mut [I],
+    ) -> (LocalSearchStatistics, Vec<usize>)
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        let mut stats = LocalSearchStatistics {
+            searched: 0,
+            improved: 0,
+            evaluations: 0,
+            avg_gain: 0.0,
+        };
+
+        let mut learned = Vec::with_capacity(children.len());
+        let mut rewritten = Vec::new();
+        let mut gain = 0.0;
+
+        for (index, child) in children.iter_mut().enumerate() {
+            let fitness = child.fitness();
+
+            let genes = child.chromosome().to_real();
+
+            if !rng.random_bool(self.probability as f64) {
+                learned.push((genes, fitness));
+                continue;
+            }
+
+            let (chromosome, improved) = self.search::<I, G>(rng, genes.clone(), fitness);
+
+            stats.searched += 1;
+            stats.evaluations += self.budget;
+
+            if improved > fitness {
+                stats.improved += 1;
+                gain += improved - fitness;
+
+                if self.learning == Learning::Lamarckian {
+                    *child = I::create(Chromosome::from_real(chromosome));
+                    rewritten.push(index);
+                }
+            }
+
+            learned.push((genes, improved));
+        }
+
+        if stats.searched > 0 {
+            stats.avg_gain = gain / stats.searched as f32;
+        }
+
+        if self.learning == Learning::Baldwinian {
+            self.learned = learned;
+        }
+
+        (stats, rewritten)
+    }
+
+    // Best chromosome found within the budget, with its fitness
+    fn search<I, G>(
+        &self,
+        rng: &mut dyn RngCore,
+        mut best: Chromosome,
+        mut fitness: f32,
+    ) -> (Chromosome, f32)
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        let evaluate = |chromosome: &Chromosome| I::create(Chromosome::from_real(chromosome.clone())).fitness();
+
+        match self.method {
+            LocalSearchMethod::HillClimbing { .. } => {
+                for _ in 0..self.budget {
+                    let mut candidate = best.clone();
+
+                    for gene in candidate.iter_mut() {
+                        *gene += self.noise.sample(rng);
+                    }
+
+                    let candidate_fitness = evaluate(&candidate);
+
+                    if candidate_fitness > fitness {
+                        best = candidate;
+                        fitness = candidate_fitness;
+                    }
+                }
+            }
+
+            LocalSearchMethod::CoordinateSearch { mut step } => {
+                let mut evaluations = 0;
+
+                'search: while evaluations < self.budget && !best.is_empty() {
+                    let mut improved = false;
+
+                    for gene in 0..best.len() {
+                        for direction in [1.0, -1.0] {
+                            if evaluations == self.budget {
+                                break 'search;
+                            }
+
+                            let mut candidate = best.clone();
+                            *candidate.iter_mut().nth(gene).unwrap() += direction * step;
+
+                            let candidate_fitness = evaluate(&candidate);
+                            evaluations += 1;
+
+                            if candidate_fitness > fitness {
+                                best = candidate;
+                                fitness = candidate_fitness;
+                                improved = true;
+                                break;
+                            }
+                        }
+                    }
+
+                    if !improved {
+                        step /= 2.0;
+                    }
+                }
+            }
+        }
+
+        (best, fitness)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{BitIndividual, SphereIndividual, TestIndividual};
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn ga() -> GeneticAlgorithm<RankSelection, UniformCrossover, GaussianMutation> {
+        GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.5, 0.5))
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        (0..6)
+            .map(|i| TestIndividual::create(vec![i as f32; 3].into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn coordinate_search_climbs_to_the_optimum() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut local_search = LocalSearch::new(
+            LocalSearchMethod::CoordinateSearch { step: 1.0 },
+            200,
+            1.0,
+            Learning::Lamarckian,
+        );
+
+        let mut children = vec![SphereIndividual::create(vec![4.0, -3.0, 0.3].into_iter().collect())];
+        let (stats, rewritten) = local_search.apply(&mut rng, &mut children);
+
+        assert_eq!(stats, LocalSearchStatistics {
+            searched: 1,
+            improved: 1,
+            evaluations: 200,
+            avg_gain: stats.avg_gain,
+        });
+
+        assert_eq!(rewritten, [0]);
+
+        approx::assert_relative_eq!(children[0].fitness(), 0.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn coordinate_search_flips_binary_genes() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut local_search = LocalSearch::new(
+            LocalSearchMethod::CoordinateSearch { step: 1.0 },
+            8,
+            1.0,
+            Learning::Lamarckian,
+        );
+
+        let mut children = vec![BitIndividual::create(vec![false, true, false].into_iter().collect())];
+        local_search.apply(&mut rng, &mut children);
+
+        assert_eq!(children[0].fitness(), 3.0);
+    }
+
+    #[test]
+    fn lamarckian_search_writes_improvements_back() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut memetic = ga().with_local_search(LocalSearch::new(
+            LocalSearchMethod::HillClimbing { step: 0.5 },
+            10,
+            1.0,
+            Learning::Lamarckian,
+        ));
+
+        let (children, stats) = Optimizer::evolve(&mut memetic, &mut rng, &population());
+
+        let (plain, _) = Optimizer::evolve(&mut ga(), &mut StdRng::seed_from_u64(42), &population());
+
+        let local_search = stats.local_search.unwrap();
+        assert_eq!(local_search.searched, 6);
+        assert!(local_search.improved > 0);
+
+        let total = |population: &[TestIndividual]| {
+            population.iter().map(TestIndividual::fitness).sum::<f32>()
+        };
+
+        approx::assert_relative_eq!(
+            total(&children) - total(&plain),
+            6.0 * local_search.avg_gain,
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn baldwinian_search_only_credits_fitness() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut memetic = ga().with_local_search(LocalSearch::new(
+            LocalSearchMethod::HillClimbing { step: 0.5 },
+            10,
+            1.0,
+            Learning::Baldwinian,
+        ));
+
+        let (children, stats) = Optimizer::evolve(&mut memetic, &mut rng, &population());
+        let (plain, _) = Optimizer::evolve(&mut ga(), &mut StdRng::seed_from_u64(42), &population());
+
+        assert!(stats.local_search.unwrap().improved > 0);
+        assert_eq!(children, plain);
+
+        // Learned fitness is only used for selection, statistics stay raw
+        let (_, stats) = Optimizer::evolve(&mut memetic, &mut rng, &children);
+        approx::assert_relative_eq!(stats.max_fitness, Statistics::new(&children).max_fitness);
+    }
+
+    #[test]
+    fn learned_fitness_sticks_to_its_children() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut local_search = LocalSearch::new(
+            LocalSearchMethod::HillClimbing { step: 0.5 },
+            10,
+            1.0,
+            Learning::Baldwinian,
+        );
+
+        let mut children = population();
+        local_search.apply(&mut rng, &mut children);
+
+        // Same size, but not the children that have learned anything
+        let mut others = population();
+        others.reverse();
+        assert!(local_search.take_learned(&others).is_none());
+
+        local_search.apply(&mut rng, &mut children);
+
+        let learned = local_search.take_learned(&children).unwrap();
+        assert!(learned.iter().zip(&children).all(|(learned, child)| *learned >= child.fitness()));
+        assert!(local_search.take_learned(&children).is_none());
+    }
+}
+// ---------------------------------------------------------------
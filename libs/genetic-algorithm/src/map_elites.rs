@@ -0,0 +1,319 @@
+use crate::*;
+
+use std::fmt::Write;
+
+// ----------------------- Definitions ---------------------------
+// MAP-Elites: the behaviour space (see `BehavioralIndividual`) is split into
+// a grid and every cell keeps the best individual that ever landed in it.
+// Parents are picked uniformly from the filled cells, so instead of a single
+// champion we end up with a whole collection of diverse, good solutions.
+#[derive(Clone, Debug)]
+pub struct MapElites<C, M> {
+    dimensions: Vec<GridDimension>,
+    crossover_method: C,
+    mutation_method: M,
+    // Flattened, row-major grid
+    cells: Vec<Option<Elite>>,
+}
+
+// Single axis of the grid, behaviours outside of <min, max> fall into the
+// first or the last bin
+#[derive(Clone, Debug)]
+pub struct GridDimension {
+    min: f32,
+    max: f32,
+    bins: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Elite {
+    pub chromosome: Chromosome,
+    pub fitness: f32,
+    pub behaviour: Vec<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MapElitesStatistics {
+    // Fitness of the population that was just inserted
+    pub fitness: Statistics,
+    pub filled_cells: usize,
+    pub coverage: f32,
+    // Sum of the fitness of every elite
+    pub qd_score: f32,
+    pub best_fitness: f32,
+    // Individuals that became an elite this generation (a cell can take
+    // more than one, see `filled_cells` for the cells)
+    pub insertions: usize,
+}
+// ---------------------------------------------------------------
+
+
+// ----------------- Grid Dimension Implementation ---------------
+impl GridDimension {
+    pub fn new(min: f32, max: f32, bins: usize) -> Self {
+        assert!(min < max);
+        assert!(bins > 0);
+
+        Self { min, max, bins }
+    }
+
+    fn bin(&self, value: f32) -> usize {
+        let position = (value - self.min) / (self.max - self.min);
+        ((position * self.bins as f32).max(0.0) as usize).min(self.bins - 1)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ------------------ MAP-Elites Implementation ------------------
+impl<C, M> MapElites<C, M>
+where
+    C: CrossoverMethod,
+    M: MutationMethod
+{
+    pub fn new(dimensions: Vec<GridDimension>, crossover_method: C, mutation_method: M) -> Self {
+        assert!(!dimensions.is_empty());
+
+        let size = dimensions.iter().map(|dimension| dimension.bins).product();
+
+        Self {
+            dimensions,
+            crossover_method,
+            mutation_method,
+            cells: vec![None; size],
+        }
+    }
+
+    // Places the individual in its cell if the cell is empty or holds a worse
+    // elite, returns whether it made it in
+    pub fn insert<I>(&mut self, individual: &I) -> bool
+    where
+        I: BehavioralIndividual,
+    {
+        let behaviour = individual.behaviour();
+        let cell = self.cell_of(&behaviour);
+
+        let better = match &self.cells[cell] {
+            Some(elite) => individual.fitness() > elite.fitness,
+            None => true,
+        };
+
+        if better {
+            self.cells[cell] = Some(Elite {
+                chromosome: individual.chromosome().clone(),
+                fitness: individual.fitness(),
+                behaviour,
+            });
+        }
+
+        better
+    }
+
+    // Inserts the evaluated population into the grid, then breeds as many
+    // children from random elites
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, MapElitesStatistics)
+    where
+        I: BehavioralIndividual,
+    {
+        assert!(!population.is_empty());
+
+        let insertions = population
+            .iter()
+            .filter(|individual| self.insert(*individual))
+            .count();
+
+        let elites: Vec<&Elite> = self.cells.iter().flatten().collect();
+
+        let new_population = (0..population.len())
+            .map(|_| {
+                // 1. Selection
+                let parent_a = &elites.choose(rng).expect("got an empty grid").chromosome;
+                let parent_b = &elites.choose(rng).expect("got an empty grid").chromosome;
+
+                // 2. Crossover
+                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+
+                // 3. Mutation
+                self.mutation_method.mutate(rng, &mut child);
+
+                I::create(child)
+            })
+            .collect();
+
+        let stats = MapElitesStatistics {
+            fitness: Statistics::new(population),
+            filled_cells: elites.len(),
+            coverage: elites.len() as f32 / self.cells.len() as f32,
+            qd_score: elites.iter().map(|elite| elite.fitness).sum(),
+            best_fitness: elites.iter().map(|elite| elite.fitness).fold(f32::NEG_INFINITY, f32::max),
+            insertions,
+        };
+
+        (new_population, stats)
+    }
+
+    pub fn dimensions(&self) -> &[GridDimension] {
+        &self.dimensions
+    }
+
+    pub fn elite(&self, coordinates: &[usize]) -> Option<&Elite> {
+        self.cells[self.index_of(coordinates)].as_ref()
+    }
+
+    // Every filled cell together with its coordinates
+    pub fn elites(&self) -> impl Iterator<Item = (Vec<usize>, &Elite)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| cell.as_ref().map(|elite| (self.coordinates_of(index), elite)))
+    }
+
+    // Exports the filled grid as CSV, one elite per row:
+    // `cell_0, ..., cell_n, fitness, behaviour_0, ..., behaviour_n`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+
+        let cells = (0..self.dimensions.len()).map(|i| format!("cell_{i}"));
+        let behaviours = (0..self.dimensions.len()).map(|i| format!("behaviour_{i}"));
+        let header: Vec<String> = cells
+            .chain(std::iter::once("fitness".to_string()))
+            .chain(behaviours)
+            .collect();
+
+        writeln!(csv, "{}", header.join(",")).unwrap();
+
+        for (coordinates, elite) in self.elites() {
+            let row: Vec<String> = coordinates
+                .iter()
+                .map(|c| c.to_string())
+                .chain(std::iter::once(elite.fitness.to_string()))
+                .chain(elite.behaviour.iter().map(|b| b.to_string()))
+                .collect();
+
+            writeln!(csv, "{}", row.join(",")).unwrap();
+        }
+
+        csv
+    }
+
+    fn cell_of(&self, behaviour: &[f32]) -> usize {
+        assert_eq!(behaviour.len(), self.dimensions.len());
+
+        let coordinates: Vec<usize> = self
+            .dimensions
+            .iter()
+            .zip(behaviour)
+            .map(|(dimension, &value)| dimension.bin(value))
+            .collect();
+
+        self.index_of(&coordinates)
+    }
+
+    fn index_of(&self, coordinates: &[usize]) -> usize {
+        assert_eq!(coordinates.len(), self.dimensions.len());
+
+        coordinates
+            .iter()
+            .zip(&self.dimensions)
+            .fold(0, |index, (&coordinate, dimension)| {
+                assert!(coordinate < dimension.bins);
+                index * dimension.bins + coordinate
+            })
+    }
+
+    fn coordinates_of(&self, mut index: usize) -> Vec<usize> {
+        let mut coordinates = vec![0; self.dimensions.len()];
+
+        for (coordinate, dimension) in coordinates.iter_mut().zip(&self.dimensions).rev() {
+            *coordinate = index % dimension.bins;
+            index /= dimension.bins;
+        }
+
+        coordinates
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::GridIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn individual(genes: [f32; 3]) -> GridIndividual {
+        GridIndividual::create(genes.into_iter().collect())
+    }
+
+    fn map_elites() -> MapElites<UniformCrossover, GaussianMutation> {
+        MapElites::new(
+            vec![GridDimension::new(0.0, 1.0, 2), GridDimension::new(0.0, 1.0, 3)],
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.1),
+        )
+    }
+
+    #[test]
+    fn keeps_the_best_per_cell() {
+        let mut grid = map_elites();
+
+        assert!(grid.insert(&individual([0.1, 0.1, 1.0])));
+        assert!(!grid.insert(&individual([0.2, 0.2, 0.5])));
+        assert!(grid.insert(&individual([0.2, 0.2, 2.0])));
+        assert!(grid.insert(&individual([0.9, 0.9, 0.1])));
+
+        assert_eq!(grid.elite(&[0, 0]).unwrap().fitness, 2.0);
+        assert_eq!(grid.elite(&[1, 2]).unwrap().fitness, 0.1);
+        assert!(grid.elite(&[1, 0]).is_none());
+    }
+
+    #[test]
+    fn out_of_range_behaviours_land_on_the_edges() {
+        let mut grid = map_elites();
+
+        grid.insert(&individual([-5.0, 5.0, 1.0]));
+
+        assert!(grid.elite(&[0, 2]).is_some());
+    }
+
+    #[test]
+    fn evolve_reports_coverage_and_qd_score() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut grid = map_elites();
+
+        let population = vec![
+            individual([0.1, 0.1, 1.0]),
+            individual([0.1, 0.2, 3.0]),
+            individual([0.9, 0.5, 2.0]),
+        ];
+
+        let (children, stats) = grid.evolve(&mut rng, &population);
+
+        assert_eq!(children.len(), 3);
+        assert_eq!(stats.filled_cells, 2);
+        assert_eq!(stats.insertions, 3);
+        approx::assert_relative_eq!(stats.coverage, 2.0 / 6.0);
+        approx::assert_relative_eq!(stats.qd_score, 5.0);
+        approx::assert_relative_eq!(stats.best_fitness, 3.0);
+    }
+
+    #[test]
+    fn exports_filled_cells() {
+        let mut grid = map_elites();
+
+        grid.insert(&individual([0.9, 0.9, 0.5]));
+        grid.insert(&individual([0.1, 0.4, 1.5]));
+
+        assert_eq!(
+            grid.to_csv(),
+            "cell_0,cell_1,fitness,behaviour_0,behaviour_1\n\
+             0,1,1.5,0.1,0.4\n\
+             1,2,0.5,0.9,0.9\n"
+        );
+    }
+}
+// ---------------------------------------------------------------
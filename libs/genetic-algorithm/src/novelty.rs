@@ -0,0 +1,295 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Individual that can describe what it did, not only how well it did it -
+// e.g. its final position or a histogram of its turns
+pub trait BehavioralIndividual: Individual {
+    fn behaviour(&self) -> Vec<f32>;
+}
+
+// Novelty search: individuals are rewarded for behaving differently from
+// the current population and from everything seen so far (the archive).
+//
+// Novelty is the mean distance to the `k` nearest behaviours, it can be
+// blended with the regular fitness through `with_fitness_weight`.
+#[derive(Clone, Debug)]
+pub struct NoveltySearch {
+    k: usize,
+    policy: ArchivePolicy,
+    fitness_weight: f32,
+    archive_limit: Option<usize>,
+    archive: Vec<Vec<f32>>,
+}
+
+// Which behaviours make it into the archive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchivePolicy {
+    // Everyone whose novelty exceeds the threshold
+    Threshold(f32),
+    // Everyone with the given probability (within <0, 1>)
+    Random(f32),
+    // The `n` most novel individuals of every generation
+    MostNovel(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct NoveltyStatistics {
+    // Raw fitness, as reported by the individuals
+    pub fitness: Statistics,
+    pub min_novelty: f32,
+    pub max_novelty: f32,
+    pub avg_novelty: f32,
+    pub archive_size: usize,
+}
+// ---------------------------------------------------------------
+
+
+// ---------------- Novelty Search Implementation ----------------
+impl NoveltySearch {
+    pub fn new(k: usize, policy: ArchivePolicy) -> Self {
+        assert!(k > 0);
+
+        if let ArchivePolicy::Random(chance) = policy {
+            assert!((0.0..=1.0).contains(&chance));
+        }
+
+        Self {
+            k,
+            policy,
+            fitness_weight: 0.0,
+            archive_limit: None,
+            archive: Vec::new(),
+        }
+    }
+
+    // 0.0 = pure novelty, 1.0 = pure fitness; both are normalized to <0, 1>
+    // within the population before blending
+    pub fn with_fitness_weight(mut self, weight: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight));
+        self.fitness_weight = weight;
+        self
+    }
+
+    // Once full, the oldest behaviours are dropped first
+    pub fn with_archive_limit(mut self, limit: usize) -> Self {
+        self.archive_limit = Some(limit);
+        self
+    }
+
+    pub fn archive(&self) -> &[Vec<f32>] {
+        &self.archive
+    }
+
+    pub fn novelty<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: BehavioralIndividual,
+    {
+        let behaviours: Vec<_> = population.iter().map(I::behaviour).collect();
+        self.novelty_of(&behaviours)
+    }
+
+    fn novelty_of(&self, behaviours: &[Vec<f32>]) -> Vec<f32> {
+        behaviours
+            .iter()
+            .enumerate()
+            .map(|(index, behaviour)| {
+                let mut distances: Vec<f32> = behaviours
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, other)| other)
+                    .chain(&self.archive)
+                    .map(|other| distance(behaviour, other))
+                    .collect();
+
+                if distances.is_empty() {
+                    return 0.0;
+                }
+
+                distances.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+                let nearest = &distances[..self.k.min(distances.len())];
+                nearest.iter().sum::<f32>() / nearest.len() as f32
+            })
+            .collect()
+    }
+
+    // Scores the population, updates the archive and lets `optimizer` breed
+    // the next generation on the novelty (or blended) score
+    pub fn evolve<O, I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        optimizer: &mut O,
+        population: &[I],
+    ) -> (Vec<I>, NoveltyStatistics)
+    where
+        O: Optimizer,
+        I: BehavioralIndividual,
+    {
+        assert!(!population.is_empty());
+
+        let behaviours: Vec<_> = population.iter().map(I::behaviour).collect();
+        let novelty = self.novelty_of(&behaviours);
+
+        let fitness: Vec<f32> = population.iter().map(I::fitness).collect();
+        let novelty_norm = normalize(&novelty);
+        let fitness_norm = normalize(&fitness);
+
+        let scores: Vec<f32> = novelty_norm
+            .iter()
+            .zip(&fitness_norm)
+            .map(|(novelty, fitness)| {
+                (1.0 - self.fitness_weight) * novelty + self.fitness_weight * fitness
+            })
+            .collect();
+
+        let new_population = evolve_scored(rng, optimizer, population, &scores);
+
+        self.extend_archive(rng, behaviours, &novelty);
+
+        let stats = NoveltyStatistics {
+            fitness: Statistics::new(population),
+            min_novelty: novelty.iter().cloned().fold(f32::INFINITY, f32::min),
+            max_novelty: novelty.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+            avg_novelty: novelty.iter().sum::<f32>() / novelty.len() as f32,
+            archive_size: self.archive.len(),
+        };
+
+        (new_population, stats)
+    }
+
+    fn extend_archive(&mut self, rng: &mut dyn RngCore, behaviours: Vec<Vec<f32>>, novelty: &[f32]) {
+        match self.policy {
+            ArchivePolicy::Threshold(threshold) => {
+                self.archive.extend(
+                    behaviours
+                        .into_iter()
+                        .zip(novelty)
+                        .filter(|(_, novelty)| **novelty > threshold)
+                        .map(|(behaviour, _)| behaviour),
+                );
+            }
+
+            ArchivePolicy::Random(chance) => {
+                for behaviour in behaviours {
+                    if rng.random_bool(chance as f64) {
+                        self.archive.push(behaviour);
+                    }
+                }
+            }
+
+            ArchivePolicy::MostNovel(count) => {
+                let mut order: Vec<usize> = (0..behaviours.len()).collect();
+                order.sort_by(|&a, &b| {
+                    novelty[b]
+                        .partial_cmp(&novelty[a])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                });
+
+                self.archive.extend(order.into_iter().take(count).map(|index| behaviours[index].clone()));
+            }
+        }
+
+        if let Some(limit) = self.archive_limit {
+            let excess = self.archive.len().saturating_sub(limit);
+            self.archive.drain(..excess);
+        }
+    }
+}
+
+fn distance(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len());
+
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f32>()
+        .sqrt()
+}
+
+// Rescales values to <0, 1>, all zeros when they're all the same
+fn normalize(values: &[f32]) -> Vec<f32> {
+    let min = values.iter().cloned().fold(f32::INFINITY, f32::min);
+    let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+    values
+        .iter()
+        .map(|value| if max > min { (value - min) / (max - min) } else { 0.0 })
+        .collect()
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn population() -> Vec<TestIndividual> {
+        [0.0, 1.0, 2.0, 10.0]
+            .iter()
+            .map(|&gene| TestIndividual::create(vec![gene].into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn novelty_is_mean_distance_to_nearest_neighbours() {
+        let novelty = NoveltySearch::new(2, ArchivePolicy::Threshold(0.0)).novelty(&population());
+
+        approx::assert_relative_eq!(novelty.as_slice(), [1.5, 1.0, 1.5, 8.5].as_ref());
+    }
+
+    #[test]
+    fn archive_counts_as_neighbours() {
+        let mut search = NoveltySearch::new(1, ArchivePolicy::Threshold(0.0));
+        search.archive.push(vec![9.5]);
+
+        let novelty = search.novelty(&population());
+
+        approx::assert_relative_eq!(novelty[3], 0.5);
+    }
+
+    #[test]
+    fn archive_policies() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let behaviours = || vec![vec![0.0], vec![1.0], vec![2.0]];
+        let novelty = [1.0, 5.0, 3.0];
+
+        let mut threshold = NoveltySearch::new(1, ArchivePolicy::Threshold(2.0));
+        threshold.extend_archive(&mut rng, behaviours(), &novelty);
+        assert_eq!(threshold.archive(), &[vec![1.0], vec![2.0]]);
+
+        let mut most_novel = NoveltySearch::new(1, ArchivePolicy::MostNovel(1));
+        most_novel.extend_archive(&mut rng, behaviours(), &novelty);
+        assert_eq!(most_novel.archive(), &[vec![1.0]]);
+
+        let mut limited = NoveltySearch::new(1, ArchivePolicy::Random(1.0)).with_archive_limit(2);
+        limited.extend_archive(&mut rng, behaviours(), &novelty);
+        assert_eq!(limited.archive(), &[vec![1.0], vec![2.0]]);
+    }
+
+    #[test]
+    fn evolve_reports_novelty() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let mut search = NoveltySearch::new(2, ArchivePolicy::MostNovel(1)).with_fitness_weight(0.5);
+        let (children, stats) = search.evolve(&mut rng, &mut ga, &population());
+
+        assert_eq!(children.len(), 4);
+        assert_eq!(stats.archive_size, 1);
+        approx::assert_relative_eq!(stats.max_novelty, 8.5);
+        approx::assert_relative_eq!(stats.fitness.max_fitness, 10.0);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,369 @@
+use crate::*;
+
+use std::cmp::Ordering;
+
+// ----------------------- Definitions ---------------------------
+// Individual judged by several objectives at once, all of them maximized
+// (negate the ones you want to minimize, e.g. distance travelled)
+pub trait MultiObjectiveIndividual {
+    fn create(chromosome: Chromosome) -> Self;
+    fn objectives(&self) -> Vec<f32>;
+    fn chromosome(&self) -> &Chromosome;
+}
+
+// NSGA-II: parents are picked with a crowded binary tournament, i.e. lower
+// Pareto front first, then larger crowding distance.
+//
+// For the full elitist loop, merge the evaluated parents with their evaluated
+// children and pass them through `pareto_survivors` before `Nsga2::evolve`.
+#[derive(Clone, Debug)]
+pub struct Nsga2<C, M> {
+    crossover_method: C,
+    mutation_method: M,
+}
+
+#[derive(Clone, Debug)]
+pub struct ParetoStatistics {
+    pub fronts: usize,
+    // Objectives of every individual in the first (non-dominated) front
+    pub pareto_front: Vec<Vec<f32>>,
+    pub min_objectives: Vec<f32>,
+    pub max_objectives: Vec<f32>,
+    pub avg_objectives: Vec<f32>,
+}
+
+// Front index and crowding distance of every individual
+#[derive(Clone, Debug)]
+struct Ranking {
+    front: Vec<usize>,
+    crowding: Vec<f32>,
+    fronts: Vec<Vec<usize>>,
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Pareto Dominance ------------------------
+// `a` dominates `b` if it's not worse in any objective and better in at least one
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    assert_eq!(a.len(), b.len());
+
+    a.iter().zip(b).all(|(a, b)| a >= b) && a.iter().zip(b).any(|(a, b)| a > b)
+}
+
+// Environmental selection: keeps the `count` best individuals by front,
+// breaking ties in the last accepted front by crowding distance
+pub fn pareto_survivors<I>(population: &[I], count: usize) -> Vec<I>
+where
+    I: MultiObjectiveIndividual + Clone,
+{
+    let objectives: Vec<_> = population.iter().map(I::objectives).collect();
+    let ranking = Ranking::new(&objectives);
+
+    let mut order: Vec<usize> = (0..population.len()).collect();
+    order.sort_by(|&a, &b| ranking.compare(a, b));
+
+    order
+        .into_iter()
+        .take(count)
+        .map(|index| population[index].clone())
+        .collect()
+}
+
+// Fast non-dominated sort, returns indices grouped by front (best front first)
+pub fn non_dominated_sort(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+
+    let mut dominated_by: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut domination_count = vec![0; n];
+    let mut fronts = vec![Vec::new()];
+
+    for p in 0..n {
+        for q in 0..n {
+            if dominates(&objectives[p], &objectives[q]) {
+                dominated_by[p].push(q);
+            } else if dominates(&objectives[q], &objectives[p]) {
+                domination_count[p] += 1;
+            }
+        }
+
+        if domination_count[p] == 0 {
+            fronts[0].push(p);
+        }
+    }
+
+    loop {
+        let mut next = Vec::new();
+
+        for &p in fronts.last().unwrap() {
+            for &q in &dominated_by[p] {
+                domination_count[q] -= 1;
+
+                if domination_count[q] == 0 {
+                    next.push(q);
+                }
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+
+        next.sort();
+        fronts.push(next);
+    }
+
+    fronts.retain(|front| !front.is_empty());
+    fronts
+}
+
+// Crowding distance of each member of a single front (same order as `front`),
+// boundary individuals get an infinite distance so they're always preferred
+pub fn crowding_distance(objectives: &[Vec<f32>], front: &[usize]) -> Vec<f32> {
+    let mut distance = vec![0.0; front.len()];
+
+    if front.is_empty() {
+        return distance;
+    }
+
+    // Values of every objective, one column per objective
+    let columns: Vec<Vec<f32>> = (0..objectives[front[0]].len())
+        .map(|objective| front.iter().map(|&index| objectives[index][objective]).collect())
+        .collect();
+
+    for values in columns {
+        let value = |position: usize| values[position];
+
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|&a, &b| value(a).partial_cmp(&value(b)).unwrap_or(Ordering::Equal));
+
+        let min = value(order[0]);
+        let max = value(order[order.len() - 1]);
+
+        distance[order[0]] = f32::INFINITY;
+        distance[order[order.len() - 1]] = f32::INFINITY;
+
+        if max - min <= 0.0 {
+            continue;
+        }
+
+        for window in order.windows(3) {
+            distance[window[1]] += (value(window[2]) - value(window[0])) / (max - min);
+        }
+    }
+
+    distance
+}
+
+impl Ranking {
+    fn new(objectives: &[Vec<f32>]) -> Self {
+        let fronts = non_dominated_sort(objectives);
+
+        let mut front = vec![0; objectives.len()];
+        let mut crowding = vec![0.0; objectives.len()];
+
+        for (rank, members) in fronts.iter().enumerate() {
+            for (&index, distance) in members.iter().zip(crowding_distance(objectives, members)) {
+                front[index] = rank;
+                crowding[index] = distance;
+            }
+        }
+
+        Self { front, crowding, fronts }
+    }
+
+    // Crowded comparison: `Less` means `a` is better than `b`
+    fn compare(&self, a: usize, b: usize) -> Ordering {
+        self.front[a]
+            .cmp(&self.front[b])
+            .then_with(|| {
+                self.crowding[b]
+                    .partial_cmp(&self.crowding[a])
+                    .unwrap_or(Ordering::Equal)
+            })
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- NSGA-II Implementation ------------------
+impl<C, M> Nsga2<C, M>
+where
+    C: CrossoverMethod,
+    M: MutationMethod
+{
+    pub fn new(crossover_method: C, mutation_method: M) -> Self {
+        Self {
+            crossover_method,
+            mutation_method,
+        }
+    }
+
+    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, ParetoStatistics)
+    where
+        I: MultiObjectiveIndividual,
+    {
+        assert!(!population.is_empty());
+
+        let objectives: Vec<_> = population.iter().map(I::objectives).collect();
+        let ranking = Ranking::new(&objectives);
+
+        let new_population = (0..population.len())
+            .map(|_| {
+                // 1. Selection
+                let parent_a = population[self.tournament(rng, &ranking)].chromosome();
+                let parent_b = population[self.tournament(rng, &ranking)].chromosome();
+
+                // 2. Crossover
+                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+
+                // 3. Mutation
+                self.mutation_method.mutate(rng, &mut child);
+
+                I::create(child)
+            })
+            .collect();
+
+        let stats = ParetoStatistics::new(&objectives, &ranking);
+
+        (new_population, stats)
+    }
+
+    // Crowded binary tournament
+    fn tournament(&self, rng: &mut dyn RngCore, ranking: &Ranking) -> usize {
+        let a = rng.random_range(0..ranking.front.len());
+        let b = rng.random_range(0..ranking.front.len());
+
+        match ranking.compare(a, b) {
+            Ordering::Greater => b,
+            _ => a,
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ---------------- Pareto Statistics Implementation -------------
+impl ParetoStatistics {
+    fn new(objectives: &[Vec<f32>], ranking: &Ranking) -> Self {
+        let dimensions = objectives[0].len();
+
+        let mut min_objectives = vec![f32::INFINITY; dimensions];
+        let mut max_objectives = vec![f32::NEG_INFINITY; dimensions];
+        let mut sum_objectives = vec![0.0; dimensions];
+
+        for values in objectives {
+            for (dimension, &value) in values.iter().enumerate() {
+                min_objectives[dimension] = min_objectives[dimension].min(value);
+                max_objectives[dimension] = max_objectives[dimension].max(value);
+                sum_objectives[dimension] += value;
+            }
+        }
+
+        Self {
+            fronts: ranking.fronts.len(),
+            pareto_front: ranking.fronts[0]
+                .iter()
+                .map(|&index| objectives[index].clone())
+                .collect(),
+            min_objectives,
+            max_objectives,
+            avg_objectives: sum_objectives
+                .into_iter()
+                .map(|sum| sum / objectives.len() as f32)
+                .collect(),
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ParetoIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn individual(genes: &[f32]) -> ParetoIndividual {
+        ParetoIndividual::create(genes.iter().cloned().collect())
+    }
+
+    #[test]
+    fn dominance() {
+        assert!(dominates(&[2.0, 2.0], &[1.0, 2.0]));
+        assert!(!dominates(&[2.0, 1.0], &[1.0, 2.0]));
+        assert!(!dominates(&[1.0, 1.0], &[1.0, 1.0]));
+    }
+
+    #[test]
+    fn sorts_into_fronts() {
+        let objectives = vec![
+            vec![1.0, 1.0],
+            vec![3.0, 1.0],
+            vec![1.0, 3.0],
+            vec![2.0, 2.0],
+            vec![0.0, 0.0],
+        ];
+
+        let fronts = non_dominated_sort(&objectives);
+
+        assert_eq!(fronts, vec![vec![1, 2, 3], vec![0], vec![4]]);
+    }
+
+    #[test]
+    fn crowding_prefers_boundaries() {
+        let objectives = vec![
+            vec![0.0, 4.0],
+            vec![1.0, 3.0],
+            vec![3.0, 1.0],
+            vec![4.0, 0.0],
+        ];
+
+        let distance = crowding_distance(&objectives, &[0, 1, 2, 3]);
+
+        assert_eq!(distance[0], f32::INFINITY);
+        assert_eq!(distance[3], f32::INFINITY);
+        approx::assert_relative_eq!(distance[1], 1.5);
+        approx::assert_relative_eq!(distance[2], 1.5);
+    }
+
+    #[test]
+    fn survivors_keep_the_first_front() {
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[3.0, 1.0]),
+            individual(&[1.0, 1.0]),
+            individual(&[1.0, 3.0]),
+        ];
+
+        let survivors = pareto_survivors(&population, 2);
+        let objectives: Vec<_> = survivors.iter().map(ParetoIndividual::objectives).collect();
+
+        assert_eq!(objectives, vec![vec![3.0, 1.0], vec![1.0, 3.0]]);
+    }
+
+    #[test]
+    fn evolve_reports_pareto_front() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let nsga2 = Nsga2::new(UniformCrossover, GaussianMutation::new(0.5, 0.5));
+
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[3.0, 1.0]),
+            individual(&[1.0, 1.0]),
+            individual(&[1.0, 3.0]),
+        ];
+
+        let (children, stats) = nsga2.evolve(&mut rng, &population);
+
+        assert_eq!(children.len(), population.len());
+        assert_eq!(stats.fronts, 3);
+        assert_eq!(stats.pareto_front, vec![vec![3.0, 1.0], vec![1.0, 3.0]]);
+        assert_eq!(stats.max_objectives, vec![3.0, 3.0]);
+        assert_eq!(stats.avg_objectives, vec![1.25, 1.25]);
+    }
+}
+// ---------------------------------------------------------------
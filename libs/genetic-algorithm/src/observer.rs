@@ -0,0 +1,138 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Gets called at every step of the GA's `Optimizer::evolve`, e.g. to log or
+// visualize which parents were picked and what crossover and mutation did
+// to their genes. Every callback does nothing by default.
+//
+// Children are identified by their index in the new population and parents
+// by their index in the old one. Callbacks take `&self`, so observers that
+// collect data need interior mutability (a `Mutex` if `evolve_seeded` runs
+// in parallel).
+pub trait Observer<G = f32> {
+    // When `false`, the GA skips the work needed only by the observer (e.g.
+    // copying every chromosome before it's mutated)
+    const ENABLED: bool = true;
+
+    fn generation_start(&self, _population: usize) {}
+
+    fn parent_selected(&self, _child: usize, _parent: usize, _chromosome: &Chromosome<G>) {}
+
+    // Comparing `offspring` with the parents tells which gene came from where
+    fn crossover_applied(
+        &self,
+        _child: usize,
+        _parent_a: &Chromosome<G>,
+        _parent_b: &Chromosome<G>,
+        _offspring: &Chromosome<G>,
+    ) {
+    }
+
+    fn mutation_applied(&self, _child: usize, _before: &Chromosome<G>, _after: &Chromosome<G>) {}
+
+    fn child_created(&self, _child: usize, _chromosome: &Chromosome<G>) {}
+
+    fn generation_end(&self, _stats: &Statistics) {}
+
+    // Children got swapped for a new population after `generation_end`,
+    // e.g. by `Restarts`
+    fn population_replaced(&self, _population: usize) {}
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Observer Implementation -------------------
+// No observer at all, compiles down to nothing
+impl<G> Observer<G> for () {
+    const ENABLED: bool = false;
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+    use std::sync::Mutex;
+
+    #[derive(Debug, Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Recorder {
+        fn record(&self, event: String) {
+            self.events.lock().unwrap().push(event);
+        }
+    }
+
+    impl Observer for Recorder {
+        fn generation_start(&self, population: usize) {
+            self.record(format!("start {population}"));
+        }
+
+        fn parent_selected(&self, child: usize, parent: usize, _chromosome: &Chromosome) {
+            self.record(format!("parent {child} <- {parent}"));
+        }
+
+        fn crossover_applied(&self, child: usize, _: &Chromosome, _: &Chromosome, _: &Chromosome) {
+            self.record(format!("crossover {child}"));
+        }
+
+        fn mutation_applied(&self, child: usize, before: &Chromosome, after: &Chromosome) {
+            let mutated = before.iter().zip(after.iter()).filter(|(a, b)| a != b).count();
+            self.record(format!("mutation {child}: {mutated}"));
+        }
+
+        fn child_created(&self, child: usize, _chromosome: &Chromosome) {
+            self.record(format!("child {child}"));
+        }
+
+        fn generation_end(&self, stats: &Statistics) {
+            self.record(format!("end {}", stats.max_fitness));
+        }
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        (0..2)
+            .map(|i| TestIndividual::create(vec![i as f32; 3].into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn sees_every_step() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(1.0, 0.5))
+            .with_observer(Recorder::default());
+
+        ga.evolve(&mut rng, &population());
+
+        let events = ga.observer().events.lock().unwrap();
+
+        assert_eq!(events.len(), 2 + 2 * 5);
+        assert_eq!(events[0], "start 2");
+        assert!(events[1].starts_with("parent 0 <- "));
+        assert_eq!(events[3], "crossover 0");
+        assert_eq!(events[4], "mutation 0: 3");
+        assert_eq!(events[5], "child 0");
+        assert_eq!(events[11], "end 3");
+    }
+
+    #[test]
+    fn does_not_change_the_evolution() {
+        let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, GaussianMutation::new(0.5, 0.5));
+        let mut observed = ga.clone().with_observer(Recorder::default());
+
+        let (expected, _) = ga.evolve(&mut StdRng::seed_from_u64(42), &population());
+        let (actual, _) = observed.evolve(&mut StdRng::seed_from_u64(42), &population());
+
+        assert_eq!(actual, expected);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,476 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Crossover picked from a few operators, e.g. when it's not clear which one
+// suits the genome. Every child gets one picked uniformly, unless the GA
+// was built with `GeneticAlgorithm::with_operator_selection`.
+pub struct CrossoverPortfolio<G = f32> {
+    operators: Vec<DynCrossoverMethod<G>>,
+}
+
+// Same as `CrossoverPortfolio`, for mutation
+pub struct MutationPortfolio<G = f32> {
+    operators: Vec<DynMutationMethod<G>>,
+}
+
+// Adaptive operator selection: every operator of a portfolio gets credited
+// with the children it bred that beat the average fitness of their parents,
+// and is then picked with a probability that follows its credit.
+//
+// Operators are numbered in the order they were given to the portfolio,
+// a GA without portfolios only ever has operator 0.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OperatorSelection {
+    credit: CreditAssignment,
+    crossover: OperatorPool,
+    mutation: OperatorPool,
+    // Operators every child of the last generation was bred with,
+    // as `[crossover, mutation]`
+    choices: Vec<[usize; 2]>,
+    // Children that got swapped for something the operators didn't breed
+    // (e.g. by the duplicate filter), they don't earn any credit
+    replaced: Vec<bool>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CreditAssignment {
+    // Credit is the success rate of an operator's children, averaged over
+    // time (the newest generation weighted by `adaptation`); operators are
+    // picked proportionally to their credit, but never with less than
+    // `min_probability`
+    ProbabilityMatching { adaptation: f32, min_probability: f32 },
+    // Credit as above; the best operator's probability moves towards the
+    // highest one allowed and everybody else's towards `min_probability`,
+    // by `learning` of the way every generation
+    AdaptivePursuit {
+        adaptation: f32,
+        learning: f32,
+        min_probability: f32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OperatorStatistics {
+    pub crossover: Vec<OperatorUsage>,
+    pub mutation: Vec<OperatorUsage>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OperatorUsage {
+    // Children of the next generation bred with the operator
+    pub children: usize,
+    pub probability: f32,
+    pub credit: f32,
+}
+
+// Probabilities and credit of either the crossover or the mutation operators
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct OperatorPool {
+    probabilities: Vec<f32>,
+    credit: Vec<f32>,
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Portfolios Implementation ---------------
+impl<G> CrossoverPortfolio<G> {
+    pub fn new(operators: Vec<DynCrossoverMethod<G>>) -> Self {
+        assert!(!operators.is_empty(), "got an empty portfolio");
+        Self { operators }
+    }
+}
+
+impl<G> CrossoverMethod<G> for CrossoverPortfolio<G> {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        let operator = rng.random_range(0..self.operators.len());
+        self.crossover_with(rng, operator, parent_a, parent_b)
+    }
+
+    fn operators(&self) -> usize {
+        self.operators.len()
+    }
+
+    fn crossover_with(
+        &self,
+        rng: &mut dyn RngCore,
+        operator: usize,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        self.operators[operator].crossover(rng, parent_a, parent_b)
+    }
+}
+
+impl<G> MutationPortfolio<G> {
+    pub fn new(operators: Vec<DynMutationMethod<G>>) -> Self {
+        assert!(!operators.is_empty(), "got an empty portfolio");
+        Self { operators }
+    }
+}
+
+impl<G> MutationMethod<G> for MutationPortfolio<G> {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        let operator = rng.random_range(0..self.operators.len());
+        self.mutate_with(rng, operator, child);
+    }
+
+    fn adapt(&mut self, feedback: &MutationFeedback) {
+        for operator in &mut self.operators {
+            operator.adapt(feedback);
+        }
+    }
+
+    fn operators(&self) -> usize {
+        self.operators.len()
+    }
+
+    fn mutate_with(&self, rng: &mut dyn RngCore, operator: usize, child: &mut Chromosome<G>) {
+        self.operators[operator].mutate(rng, child);
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ---------------- Operator Selection Implementation ------------
+impl OperatorSelection {
+    pub fn new(credit: CreditAssignment) -> Self {
+        let (CreditAssignment::ProbabilityMatching { adaptation, min_probability }
+        | CreditAssignment::AdaptivePursuit { adaptation, min_probability, .. }) = credit;
+
+        assert!(adaptation > 0.0 && adaptation <= 1.0);
+        assert!((0.0..1.0).contains(&min_probability));
+
+        if let CreditAssignment::AdaptivePursuit { learning, .. } = credit {
+            assert!(learning > 0.0 && learning <= 1.0);
+        }
+
+        Self {
+            credit,
+            crossover: OperatorPool::default(),
+            mutation: OperatorPool::default(),
+            choices: Vec::new(),
+            replaced: Vec::new(),
+        }
+    }
+
+    pub fn credit_assignment(&self) -> CreditAssignment {
+        self.credit
+    }
+
+    // Credits the operators the last generation was bred with, given which of
+    // its children beat their parents (see `Adaptation::successes`)
+    pub(crate) fn credit(&mut self, successes: Option<&[bool]>, crossovers: usize, mutations: usize) {
+        self.crossover.resize(crossovers, self.credit);
+        self.mutation.resize(mutations, self.credit);
+
+        let Some(successes) = successes else {
+            return;
+        };
+
+        if successes.len() != self.choices.len() {
+            return;
+        }
+
+        for (pool, kind) in [(&mut self.crossover, 0), (&mut self.mutation, 1)] {
+            let operators = pool.probabilities.len();
+            let mut bred = vec![0; operators];
+            let mut successful = vec![0; operators];
+
+            let credited = self
+                .choices
+                .iter()
+                .zip(successes)
+                .zip(&self.replaced)
+                .filter(|(_, replaced)| !**replaced)
+                .map(|(credited, _)| credited);
+
+            for (choice, &success) in credited {
+                bred[choice[kind]] += 1;
+                successful[choice[kind]] += success as usize;
+            }
+
+            let rewards: Vec<Option<f32>> = bred
+                .iter()
+                .zip(&successful)
+                .map(|(&bred, &successful)| (bred > 0).then(|| successful as f32 / bred as f32))
+                .collect();
+
+            pool.update(&rewards, self.credit);
+        }
+    }
+
+    // Picks the operators every child of the next generation gets bred with
+    pub(crate) fn choose(&mut self, rng: &mut dyn RngCore, children: usize) {
+        self.choices = (0..children)
+            .map(|_| [self.crossover.pick(rng), self.mutation.pick(rng)])
+            .collect();
+
+        self.replaced = vec![false; children];
+    }
+
+    // Keeps the operators of the given children out of the next credit
+    pub(crate) fn replaced(&mut self, children: &[usize]) {
+        for &child in children {
+            if let Some(replaced) = self.replaced.get_mut(child) {
+                *replaced = true;
+            }
+        }
+    }
+
+    // Operators picked for `child` by the last `choose`
+    pub(crate) fn choice(&self, child: usize) -> Option<[usize; 2]> {
+        self.choices.get(child).copied()
+    }
+
+    pub fn statistics(&self) -> OperatorStatistics {
+        OperatorStatistics {
+            crossover: self.crossover.usage(self.choices.iter().map(|choice| choice[0])),
+            mutation: self.mutation.usage(self.choices.iter().map(|choice| choice[1])),
+        }
+    }
+}
+
+impl OperatorPool {
+    // Starts over with every operator equally likely whenever the number of
+    // operators changes (e.g. after `GeneticAlgorithm::with_operator_selection`)
+    fn resize(&mut self, operators: usize, credit: CreditAssignment) {
+        if self.probabilities.len() == operators {
+            return;
+        }
+
+        let (CreditAssignment::ProbabilityMatching { min_probability, .. }
+        | CreditAssignment::AdaptivePursuit { min_probability, .. }) = credit;
+
+        assert!(
+            min_probability * operators as f32 <= 1.0,
+            "min_probability is too high for {operators} operators"
+        );
+
+        self.probabilities = vec![1.0 / operators as f32; operators];
+        self.credit = vec![0.0; operators];
+    }
+
+    // `rewards` holds the success rate of every operator that bred anything
+    fn update(&mut self, rewards: &[Option<f32>], credit: CreditAssignment) {
+        let operators = self.probabilities.len() as f32;
+
+        let (CreditAssignment::ProbabilityMatching { adaptation, min_probability }
+        | CreditAssignment::AdaptivePursuit { adaptation, min_probability, .. }) = credit;
+
+        for (credit, reward) in self.credit.iter_mut().zip(rewards) {
+            if let Some(reward) = reward {
+                *credit += adaptation * (reward - *credit);
+            }
+        }
+
+        let total: f32 = self.credit.iter().sum();
+
+        // Nothing has succeeded so far, so there's nothing to go by
+        if total <= 0.0 {
+            return;
+        }
+
+        match credit {
+            CreditAssignment::ProbabilityMatching { .. } => {
+                for (probability, credit) in self.probabilities.iter_mut().zip(&self.credit) {
+                    *probability = min_probability + (1.0 - operators * min_probability) * credit / total;
+                }
+            }
+
+            CreditAssignment::AdaptivePursuit { learning, .. } => {
+                let max_probability = 1.0 - (operators - 1.0) * min_probability;
+
+                let best = (0..self.credit.len())
+                    .max_by(|&a, &b| {
+                        self.credit[a]
+                            .partial_cmp(&self.credit[b])
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    })
+                    .unwrap();
+
+                for (operator, probability) in self.probabilities.iter_mut().enumerate() {
+                    let target = if operator == best { max_probability } else { min_probability };
+                    *probability += learning * (target - *probability);
+                }
+            }
+        }
+    }
+
+    fn pick(&self, rng: &mut dyn RngCore) -> usize {
+        if self.probabilities.len() < 2 {
+            return 0;
+        }
+
+        let operators: Vec<usize> = (0..self.probabilities.len()).collect();
+
+        *operators
+            .choose_weighted(rng, |&operator| self.probabilities[operator])
+            .expect("got invalid operator probabilities")
+    }
+
+    fn usage(&self, choices: impl Iterator<Item = usize>) -> Vec<OperatorUsage> {
+        let mut usage: Vec<OperatorUsage> = self
+            .probabilities
+            .iter()
+            .zip(&self.credit)
+            .map(|(&probability, &credit)| OperatorUsage {
+                children: 0,
+                probability,
+                credit,
+            })
+            .collect();
+
+        for operator in choices {
+            usage[operator].children += 1;
+        }
+
+        usage
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    // Fitness is the sum of the genes, so only the growing mutation ever
+    // breeds children that beat their parents
+    struct Shift(f32);
+
+    impl MutationMethod for Shift {
+        fn mutate(&self, _: &mut dyn RngCore, child: &mut Chromosome) {
+            for gene in child.iter_mut() {
+                *gene += self.0;
+            }
+        }
+    }
+
+    fn ga(credit: CreditAssignment) -> GeneticAlgorithm<RankSelection, UniformCrossover, MutationPortfolio> {
+        GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            MutationPortfolio::new(vec![Box::new(Shift(-100.0)), Box::new(Shift(100.0))]),
+        )
+        .with_operator_selection(OperatorSelection::new(credit))
+    }
+
+    fn run(credit: CreditAssignment, generations: usize) -> Vec<Statistics> {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ga = ga(credit);
+
+        let mut population: Vec<TestIndividual> = (0..20)
+            .map(|i| TestIndividual::create(vec![i as f32; 2].into_iter().collect()))
+            .collect();
+
+        (0..generations)
+            .map(|_| {
+                let stats;
+                (population, stats) = Optimizer::evolve(&mut ga, &mut rng, &population);
+                stats
+            })
+            .collect()
+    }
+
+    #[test]
+    fn probability_matching_favors_the_successful_operator() {
+        let credit = CreditAssignment::ProbabilityMatching { adaptation: 0.5, min_probability: 0.1 };
+        let history = run(credit, 10);
+
+        // Nothing to go by before the first children have been evaluated
+        let first = history[0].operators.as_ref().unwrap();
+        assert_eq!(first.mutation[0].probability, 0.5);
+
+        let last = history[9].operators.as_ref().unwrap();
+        approx::assert_relative_eq!(last.mutation[0].probability, 0.1);
+        approx::assert_relative_eq!(last.mutation[1].probability, 0.9);
+        assert!(last.mutation[1].credit > 0.9);
+        assert!(last.mutation[1].children > last.mutation[0].children);
+        assert_eq!(last.mutation.iter().map(|usage| usage.children).sum::<usize>(), 20);
+
+        // Single crossover gets everything, whatever its credit
+        assert_eq!(last.crossover.len(), 1);
+        assert_eq!((last.crossover[0].children, last.crossover[0].probability), (20, 1.0));
+    }
+
+    #[test]
+    fn adaptive_pursuit_moves_towards_the_best_operator() {
+        let credit = CreditAssignment::AdaptivePursuit {
+            adaptation: 0.5,
+            learning: 0.5,
+            min_probability: 0.05,
+        };
+
+        let probabilities: Vec<f32> = run(credit, 4)
+            .iter()
+            .map(|stats| stats.operators.as_ref().unwrap().mutation[1].probability)
+            .collect();
+
+        approx::assert_relative_eq!(probabilities.as_slice(), [0.5, 0.725, 0.8375, 0.89375].as_ref());
+    }
+
+    #[test]
+    fn replaced_children_earn_no_credit() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        // Children with a single gene are clones of a parent unless they got
+        // shifted, so every child of `Shift(0.0)` gets remutated by either
+        // operator - the successful ones must not be credited to it
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            MutationPortfolio::new(vec![Box::new(Shift(0.0)), Box::new(Shift(100.0))]),
+        )
+        .with_operator_selection(OperatorSelection::new(CreditAssignment::ProbabilityMatching {
+            adaptation: 0.5,
+            min_probability: 0.1,
+        }))
+        .with_duplicate_filter(DuplicateFilter::new(0.0, DuplicateAction::Remutate { attempts: 1 }));
+
+        let mut population: Vec<TestIndividual> = (0..20)
+            .map(|i| TestIndividual::create(vec![i as f32].into_iter().collect()))
+            .collect();
+
+        let mut stats = Statistics::new(&population);
+
+        for _ in 0..5 {
+            (population, stats) = Optimizer::evolve(&mut ga, &mut rng, &population);
+        }
+
+        let mutation = &stats.operators.unwrap().mutation;
+
+        assert_eq!(mutation[0].credit, 0.0);
+        assert!(mutation[1].credit > 0.9);
+    }
+
+    #[test]
+    fn portfolios_without_selection_pick_uniformly() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let portfolio = MutationPortfolio::new(vec![Box::new(Shift(-100.0)), Box::new(Shift(100.0))]);
+        assert_eq!(portfolio.operators(), 2);
+
+        let shifted: Vec<f32> = (0..100)
+            .map(|_| {
+                let mut chromosome: Chromosome = vec![0.0].into_iter().collect();
+                portfolio.mutate(&mut rng, &mut chromosome);
+                chromosome[0]
+            })
+            .collect();
+
+        let grown = shifted.iter().filter(|&&gene| gene > 0.0).count();
+        assert!((30..70).contains(&grown));
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,516 @@
+use crate::*;
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    ops::RangeInclusive,
+    str::FromStr,
+};
+
+// ------------------------- Error -------------------------------
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegistryError {
+    Syntax(String),
+    UnknownOperator {
+        kind: &'static str,
+        name: String,
+    },
+    MissingParameter {
+        operator: String,
+        parameter: String,
+    },
+    InvalidParameter {
+        operator: String,
+        parameter: String,
+        value: f32,
+    },
+    // A portfolio got no operator specs at all
+    EmptyPortfolio {
+        kind: &'static str,
+    },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Syntax(spec) => {
+                write!(f, "Invalid operator `{spec}`, expected `name` or `name(param = value, ...)`")
+            }
+            RegistryError::UnknownOperator { kind, name } => {
+                write!(f, "Unknown {kind} operator `{name}`")
+            }
+            RegistryError::MissingParameter { operator, parameter } => {
+                write!(f, "Operator `{operator}` is missing parameter `{parameter}`")
+            }
+            RegistryError::InvalidParameter { operator, parameter, value } => {
+                write!(f, "Operator `{operator}` got an invalid value for `{parameter}`: {value}")
+            }
+            RegistryError::EmptyPortfolio { kind } => {
+                write!(f, "Got an empty {kind} portfolio, expected at least one operator")
+            }
+        }
+    }
+}
+
+impl Error for RegistryError {}
+// ---------------------------------------------------------------
+
+
+// ----------------------- Definitions ---------------------------
+// Operator name together with its parameters, written as
+// `gaussian(chance = 0.01, coeff = 0.3)` (or just `rank` without any)
+#[derive(Clone, Debug, PartialEq)]
+pub struct OperatorConfig {
+    pub name: String,
+    pub params: BTreeMap<String, f32>,
+}
+
+type Factory<T> = Box<dyn Fn(&OperatorConfig) -> Result<T, RegistryError> + Send + Sync>;
+
+// Builds operators by name, so that they can be picked from a config file
+// or a UI instead of being fixed at compile time
+pub struct Registry<G = f32> {
+    selection: BTreeMap<String, Factory<DynSelectionMethod>>,
+    crossover: BTreeMap<String, Factory<DynCrossoverMethod<G>>>,
+    mutation: BTreeMap<String, Factory<DynMutationMethod<G>>>,
+}
+// ---------------------------------------------------------------
+
+
+// --------------- Operator Config Implementation ----------------
+impl OperatorConfig {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_param(mut self, name: &str, value: f32) -> Self {
+        self.params.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Result<f32, RegistryError> {
+        self.params
+            .get(name)
+            .copied()
+            .ok_or_else(|| RegistryError::MissingParameter {
+                operator: self.name.clone(),
+                parameter: name.to_string(),
+            })
+    }
+
+    pub fn param_or(&self, name: &str, default: f32) -> f32 {
+        self.params.get(name).copied().unwrap_or(default)
+    }
+
+    // Parameter that has to lie within <0, 1>
+    pub fn probability(&self, name: &str) -> Result<f32, RegistryError> {
+        self.param_in(name, 0.0..=1.0)
+    }
+
+    pub fn param_in(&self, name: &str, range: RangeInclusive<f32>) -> Result<f32, RegistryError> {
+        let value = self.param(name)?;
+        self.validate(name, value, range.contains(&value))
+    }
+
+    // Turns a value that failed a check into `RegistryError::InvalidParameter`
+    pub fn validate(&self, name: &str, value: f32, valid: bool) -> Result<f32, RegistryError> {
+        if valid {
+            Ok(value)
+        } else {
+            Err(RegistryError::InvalidParameter {
+                operator: self.name.clone(),
+                parameter: name.to_string(),
+                value,
+            })
+        }
+    }
+}
+
+impl FromStr for OperatorConfig {
+    type Err = RegistryError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let syntax = || RegistryError::Syntax(spec.to_string());
+        let spec = spec.trim();
+
+        let (name, params) = match spec.split_once('(') {
+            Some((name, rest)) => (name, rest.strip_suffix(')').ok_or_else(syntax)?),
+            None => (spec, ""),
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(syntax());
+        }
+
+        let mut config = OperatorConfig::new(name);
+
+        for param in params.split(',').filter(|param| !param.trim().is_empty()) {
+            let (key, value) = param.split_once('=').ok_or_else(syntax)?;
+            let value = value.trim().parse().map_err(|_| syntax())?;
+
+            config = config.with_param(key.trim(), value);
+        }
+
+        Ok(config)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// ------------------- Registry Implementation -------------------
+impl<G> Registry<G>
+where
+    G: Clone + 'static,
+{
+    // Comes with the operators that work for any kind of genes:
+    // `rank`, `roulette`, `scaled(...)` and `scaled_rank` (see
+    // `scaled_roulette`), `uniform`, `swap(chance)` and `inversion(chance)`
+    pub fn new() -> Self {
+        Self {
+            selection: BTreeMap::new(),
+            crossover: BTreeMap::new(),
+            mutation: BTreeMap::new(),
+        }
+        .with_selection("rank", |_| Ok(Box::new(RankSelection)))
+        .with_selection("roulette", |_| Ok(Box::new(RouletteWheelSelection)))
+        .with_selection("scaled", scaled_roulette)
+        .with_selection("scaled_rank", scaled_roulette)
+        .with_crossover("uniform", |_| Ok(Box::new(UniformCrossover)))
+        .with_mutation("swap", |config| Ok(Box::new(SwapMutation::new(config.probability("chance")?))))
+        .with_mutation("inversion", |config| {
+            Ok(Box::new(InversionMutation::new(config.probability("chance")?)))
+        })
+    }
+
+    // Registering an already known name replaces its factory
+    pub fn with_selection<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&OperatorConfig) -> Result<DynSelectionMethod, RegistryError> + Send + Sync + 'static,
+    {
+        self.selection.insert(name.to_string(), Box::new(factory));
+        self
+    }
+
+    pub fn with_crossover<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&OperatorConfig) -> Result<DynCrossoverMethod<G>, RegistryError> + Send + Sync + 'static,
+    {
+        self.crossover.insert(name.to_string(), Box::new(factory));
+        self
+    }
+
+    pub fn with_mutation<F>(mut self, name: &str, factory: F) -> Self
+    where
+        F: Fn(&OperatorConfig) -> Result<DynMutationMethod<G>, RegistryError> + Send + Sync + 'static,
+    {
+        self.mutation.insert(name.to_string(), Box::new(factory));
+        self
+    }
+
+    pub fn selections(&self) -> impl Iterator<Item = &str> {
+        self.selection.keys().map(String::as_str)
+    }
+
+    pub fn crossovers(&self) -> impl Iterator<Item = &str> {
+        self.crossover.keys().map(String::as_str)
+    }
+
+    pub fn mutations(&self) -> impl Iterator<Item = &str> {
+        self.mutation.keys().map(String::as_str)
+    }
+
+    pub fn selection(&self, spec: &str) -> Result<DynSelectionMethod, RegistryError> {
+        Self::build(&self.selection, "selection", spec)
+    }
+
+    pub fn crossover(&self, spec: &str) -> Result<DynCrossoverMethod<G>, RegistryError> {
+        Self::build(&self.crossover, "crossover", spec)
+    }
+
+    pub fn mutation(&self, spec: &str) -> Result<DynMutationMethod<G>, RegistryError> {
+        Self::build(&self.mutation, "mutation", spec)
+    }
+
+    // Every spec becomes one operator of a `CrossoverPortfolio`
+    pub fn crossover_portfolio(&self, specs: &[&str]) -> Result<DynCrossoverMethod<G>, RegistryError> {
+        if specs.is_empty() {
+            return Err(RegistryError::EmptyPortfolio { kind: "crossover" });
+        }
+
+        let operators = specs.iter().map(|spec| self.crossover(spec)).collect::<Result<_, _>>()?;
+
+        Ok(Box::new(CrossoverPortfolio::new(operators)))
+    }
+
+    // Every spec becomes one operator of a `MutationPortfolio`
+    pub fn mutation_portfolio(&self, specs: &[&str]) -> Result<DynMutationMethod<G>, RegistryError> {
+        if specs.is_empty() {
+            return Err(RegistryError::EmptyPortfolio { kind: "mutation" });
+        }
+
+        let operators = specs.iter().map(|spec| self.mutation(spec)).collect::<Result<_, _>>()?;
+
+        Ok(Box::new(MutationPortfolio::new(operators)))
+    }
+
+    pub fn genetic_algorithm(
+        &self,
+        selection: &str,
+        crossover: &str,
+        mutation: &str,
+    ) -> Result<DynGeneticAlgorithm<G>, RegistryError> {
+        Ok(GeneticAlgorithm::new(
+            self.selection(selection)?,
+            self.crossover(crossover)?,
+            self.mutation(mutation)?,
+        ))
+    }
+
+    fn build<T>(
+        factories: &BTreeMap<String, Factory<T>>,
+        kind: &'static str,
+        spec: &str,
+    ) -> Result<T, RegistryError> {
+        let config: OperatorConfig = spec.parse()?;
+
+        let factory = factories
+            .get(&config.name)
+            .ok_or_else(|| RegistryError::UnknownOperator {
+                kind,
+                name: config.name.clone(),
+            })?;
+
+        factory(&config)
+    }
+}
+
+// Roulette wheel on scaled fitness, the parameter picks the transform:
+// `scaled(multiple)` is linear, `scaled(c)` sigma truncation,
+// `scaled(exponent)` power law and plain `scaled` windowing; `scaled_rank`
+// (without any parameters) is rank normalization
+fn scaled_roulette(config: &OperatorConfig) -> Result<DynSelectionMethod, RegistryError> {
+    let mut params = config.params.iter();
+
+    let invalid = |name: &String, value: f32| RegistryError::InvalidParameter {
+        operator: config.name.clone(),
+        parameter: name.clone(),
+        value,
+    };
+
+    let transform = match (params.next(), params.next()) {
+        (None, _) if config.name == "scaled_rank" => FitnessTransform::Rank,
+        (None, _) => FitnessTransform::Windowing,
+
+        (Some((name, &value)), None) if config.name == "scaled" => match name.as_str() {
+            "multiple" => FitnessTransform::Linear { multiple: config.validate(name, value, value > 1.0)? },
+            "c" => FitnessTransform::SigmaTruncation { c: config.validate(name, value, value > 0.0)? },
+            "exponent" => FitnessTransform::PowerLaw { exponent: config.validate(name, value, value > 0.0)? },
+            _ => return Err(invalid(name, value)),
+        },
+
+        // Unknown parameter, or more than a single transform at a time
+        (Some((name, &value)), None) | (Some(_), Some((name, &value))) => {
+            return Err(invalid(name, value));
+        }
+    };
+
+    Ok(Box::new(Scaled::new(RouletteWheelSelection, transform)))
+}
+
+// Everything from `Registry::new` plus the gaussian mutations:
+// `gaussian(chance, coeff)`,
+// `one_fifth(chance, coeff, factor = 0.82, max_coeff = 10 * coeff)`,
+// `stagnation_boost(chance, coeff, after, factor)` and
+// `decay(chance, coeff, rate, min_chance = 0, min_coeff = 0)`
+impl Default for Registry {
+    fn default() -> Self {
+        let adaptive = |config: &OperatorConfig, control| -> Result<DynMutationMethod, RegistryError> {
+            Ok(Box::new(AdaptiveGaussianMutation::new(
+                config.probability("chance")?,
+                config.param("coeff")?,
+                control,
+            )))
+        };
+
+        Self::new()
+            .with_mutation("gaussian", |config| {
+                Ok(Box::new(GaussianMutation::new(
+                    config.probability("chance")?,
+                    config.param("coeff")?,
+                )))
+            })
+            .with_mutation("one_fifth", move |config| {
+                let factor = config.param_or("factor", 0.82);
+                let factor = config.validate("factor", factor, factor > 0.0 && factor < 1.0)?;
+                let coeff = config.param("coeff")?;
+                let max_coeff = config.param_or("max_coeff", 10.0 * coeff);
+                let max_coeff = config.validate("max_coeff", max_coeff, max_coeff >= coeff)?;
+                adaptive(config, MutationControl::OneFifthRule { factor, max_coeff })
+            })
+            .with_mutation("stagnation_boost", move |config| {
+                let after = config.param("after")?;
+                let after = config.validate("after", after, after >= 0.0)? as usize;
+                let factor = config.param("factor")?;
+                let factor = config.validate("factor", factor, factor > 0.0)?;
+                adaptive(config, MutationControl::StagnationBoost { after, factor })
+            })
+            .with_mutation("decay", move |config| {
+                let rate = config.param("rate")?;
+                let rate = config.validate("rate", rate, rate > 0.0 && rate <= 1.0)?;
+                let min_chance = config.param_or("min_chance", 0.0);
+                let min_coeff = config.param_or("min_coeff", 0.0);
+                let min = MutationParameters {
+                    chance: config.validate("min_chance", min_chance, (0.0..=1.0).contains(&min_chance))?,
+                    coeff: config.validate("min_coeff", min_coeff, min_coeff >= 0.0)?,
+                };
+                adaptive(config, MutationControl::Decay { rate, min })
+            })
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    #[test]
+    fn parses_operator_configs() {
+        assert_eq!("rank".parse(), Ok(OperatorConfig::new("rank")));
+
+        assert_eq!(
+            " gaussian( chance = 0.01, coeff=0.3 ) ".parse(),
+            Ok(OperatorConfig::new("gaussian").with_param("chance", 0.01).with_param("coeff", 0.3))
+        );
+
+        assert!("gaussian(chance)".parse::<OperatorConfig>().is_err());
+        assert!("gaussian(chance = 0.1".parse::<OperatorConfig>().is_err());
+        assert!("(chance = 0.1)".parse::<OperatorConfig>().is_err());
+    }
+
+    #[test]
+    fn reports_bad_operators() {
+        let registry = Registry::default();
+
+        assert!(matches!(
+            registry.selection("boltzmann"),
+            Err(RegistryError::UnknownOperator { kind: "selection", .. })
+        ));
+
+        assert!(matches!(
+            registry.selection("scaled(multiple = 0.5)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.selection("scaled(c = 2, exponent = 2)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.mutation("gaussian(chance = 0.1)"),
+            Err(RegistryError::MissingParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.mutation("gaussian(chance = 2.0, coeff = 0.3)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.mutation("one_fifth(chance = 0.1, coeff = 0.3, factor = 1.5)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.mutation("decay(chance = 0.1, coeff = 0.2, rate = 0.9, min_chance = 2)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(matches!(
+            registry.mutation("one_fifth(chance = 0.1, coeff = 0.3, max_coeff = 0.1)"),
+            Err(RegistryError::InvalidParameter { .. })
+        ));
+
+        assert!(registry.mutation("one_fifth(chance = 0.1, coeff = 0.3)").is_ok());
+
+        assert!(matches!(
+            registry.mutation_portfolio(&[]),
+            Err(RegistryError::EmptyPortfolio { kind: "mutation" })
+        ));
+    }
+
+    #[test]
+    fn builds_a_working_genetic_algorithm() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = Registry::default()
+            .genetic_algorithm("rank", "uniform", "gaussian(chance = 0.5, coeff = 0.5)")
+            .unwrap();
+
+        let population: Vec<TestIndividual> = (0..4)
+            .map(|i| TestIndividual::create(vec![i as f32; 3].into_iter().collect()))
+            .collect();
+
+        let (children, stats) = ga.evolve(&mut rng, &population);
+
+        assert_eq!(children.len(), 4);
+        approx::assert_relative_eq!(stats.max_fitness, 9.0);
+    }
+
+    #[test]
+    fn builds_fitness_proportional_selections() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let registry = Registry::default();
+
+        // Negative fitness is fine once it's scaled
+        let fitness = [-3.0, -3.0, 5.0];
+
+        for spec in [
+            "scaled",
+            "scaled(multiple = 2)",
+            "scaled(c = 1)",
+            "scaled(exponent = 2)",
+            "scaled_rank",
+        ] {
+            let selection = registry.selection(spec).unwrap();
+            let best = (0..100).filter(|_| selection.select_index(&mut rng, &fitness) == 2).count();
+
+            // Rank normalization gives the best one half of the picks
+            assert!(best > 40, "{spec}");
+        }
+
+        let roulette = registry.selection("roulette").unwrap();
+        assert!((0..20).all(|_| roulette.select_index(&mut rng, &[0.0, 1.0]) == 1));
+
+        for spec in ["scaled(exponant = 2)", "scaled(c = 1, exponent = 2)", "scaled_rank(c = 1)"] {
+            assert!(
+                matches!(registry.selection(spec), Err(RegistryError::InvalidParameter { .. })),
+                "{spec}"
+            );
+        }
+    }
+
+    #[test]
+    fn custom_operators_can_be_registered() {
+        let registry = Registry::<bool>::new()
+            .with_mutation("bit_flip", |config| {
+                Ok(Box::new(BitFlipMutation::new(config.probability("chance")?)))
+            });
+
+        assert_eq!(registry.mutations().collect::<Vec<_>>(), ["bit_flip", "inversion", "swap"]);
+        assert!(registry.mutation("bit_flip(chance = 0.1)").is_ok());
+    }
+}
+// ---------------------------------------------------------------
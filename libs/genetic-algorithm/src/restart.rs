@@ -0,0 +1,339 @@
+use crate::*;
+
+use std::time::Instant;
+
+// ----------------------- Definitions ---------------------------
+// Restarts the search once `trigger` is met, see
+// `GeneticAlgorithm::with_restarts`.
+//
+// Triggers are the runner's termination conditions, checked against the
+// generations since the last restart only, e.g. `Termination::Stagnation(50)`
+// or `Termination::DiversityCollapse(0.1)`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Restarts {
+    trigger: Termination,
+    strategy: RestartStrategy,
+    // Generations since the last restart
+    history: Vec<GenerationStatistics>,
+    generation: usize,
+    // Generations left of the current hyper-mutation burst
+    burst: usize,
+    // When the generations since the last restart started, only kept for
+    // triggers that use the clock (it isn't there on wasm32-unknown-unknown)
+    #[serde(skip)]
+    since: Option<Instant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum RestartStrategy {
+    // The best `elites` survive, everybody else gets random genes from `range`
+    PartialReseed { elites: usize, range: (f32, f32) },
+    // IPOP: re-seeded like `PartialReseed`, with the population growing by
+    // `factor` on every restart (up to `max` individuals)
+    IncreasePopulation {
+        factor: f32,
+        max: usize,
+        elites: usize,
+        range: (f32, f32),
+    },
+    // For the next `generations` generations every child gets an extra
+    // gaussian mutation on top of the regular one
+    HyperMutation {
+        chance: f32,
+        coeff: f32,
+        generations: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RestartEvent {
+    // Generation (since the GA got its restarts) whose children got restarted
+    pub generation: usize,
+    pub strategy: RestartStrategy,
+    // Population size after the restart
+    pub population: usize,
+}
+// ---------------------------------------------------------------
+
+
+// ---------------------- Restarts Implementation ----------------
+impl Restarts {
+    pub fn new(trigger: Termination, strategy: RestartStrategy) -> Self {
+        match &strategy {
+            RestartStrategy::PartialReseed { range, .. } => assert!(range.0 < range.1),
+
+            RestartStrategy::IncreasePopulation { factor, range, .. } => {
+                assert!(*factor >= 1.0);
+                assert!(range.0 < range.1);
+            }
+
+            RestartStrategy::HyperMutation { chance, generations, .. } => {
+                assert!((0.0..=1.0).contains(chance));
+                assert!(*generations > 0);
+            }
+        }
+
+        Self {
+            trigger,
+            strategy,
+            history: Vec::new(),
+            generation: 0,
+            burst: 0,
+            since: None,
+        }
+    }
+
+    pub fn trigger(&self) -> &Termination {
+        &self.trigger
+    }
+
+    pub fn strategy(&self) -> &RestartStrategy {
+        &self.strategy
+    }
+
+    // Records the evaluated `population` and restarts its `children` if the
+    // trigger is met
+    pub(crate) fn apply<I, G>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        stats: &Statistics,
+        children: &mut Vec<I>,
+    ) -> Option<RestartEvent>
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        if self.trigger.uses_clock() && self.since.is_none() {
+            self.since = Some(Instant::now());
+        }
+
+        let elapsed = || self.since.map(|since| since.elapsed()).unwrap_or_default();
+
+        // Same as the clock, the diversity is only measured for triggers
+        // that look at it
+        let diversity = if self.trigger.uses_diversity() { diversity(population) } else { f32::NAN };
+
+        self.history.push(GenerationStatistics {
+            generation: self.history.len(),
+            stats: stats.clone(),
+            diversity,
+            elapsed: elapsed(),
+        });
+
+        let generation = self.generation;
+        self.generation += 1;
+
+        let restarted = self.trigger.is_met(&self.history, elapsed());
+
+        if restarted {
+            self.history.clear();
+            self.since = None;
+
+            match self.strategy {
+                RestartStrategy::PartialReseed { elites, range } => {
+                    *children = Self::reseed(rng, population, population.len(), elites, range);
+                }
+
+                RestartStrategy::IncreasePopulation { factor, max, elites, range } => {
+                    let size = ((population.len() as f32 * factor).ceil() as usize)
+                        .min(max)
+                        .max(population.len());
+
+                    *children = Self::reseed(rng, population, size, elites, range);
+                }
+
+                RestartStrategy::HyperMutation { generations, .. } => self.burst = generations,
+            }
+        }
+
+        if let RestartStrategy::HyperMutation { chance, coeff, .. } = self.strategy
+            && self.burst > 0
+        {
+            self.burst -= 1;
+
+            let mutation = GaussianMutation::new(chance, coeff);
+
+            for child in children.iter_mut() {
+                let mut chromosome = child.chromosome().to_real();
+                mutation.mutate(rng, &mut chromosome);
+                *child = I::create(Chromosome::from_real(chromosome));
+            }
+        }
+
+        restarted.then(|| RestartEvent {
+            generation,
+            strategy: self.strategy.clone(),
+            population: children.len(),
+        })
+    }
+
+    // Best `elites` of `population` followed by random individuals
+    fn reseed<I, G>(
+        rng: &mut dyn RngCore,
+        population: &[I],
+        size: usize,
+        elites: usize,
+        (min, max): (f32, f32),
+    ) -> Vec<I>
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        let mut ranked: Vec<&I> = population.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.fitness()
+                .partial_cmp(&a.fitness())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        let genes = population[0].chromosome().len();
+        let elites = elites.min(size);
+
+        ranked
+            .into_iter()
+            .take(elites)
+            .map(|elite| I::create(elite.chromosome().clone()))
+            .chain((elites..size).map(|_| {
+                I::create(Chromosome::from_real((0..genes).map(|_| rng.random_range(min..=max)).collect()))
+            }))
+            .collect()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{BitIndividual, TestIndividual};
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    // Nothing ever changes, so the run stagnates right away
+    fn ga(strategy: RestartStrategy) -> GeneticAlgorithm<RankSelection, UniformCrossover, GaussianMutation> {
+        GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.0, 0.0),
+        )
+        .with_restarts(Restarts::new(Termination::Stagnation(2), strategy))
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        (0..4)
+            .map(|i| TestIndividual::create(vec![i as f32; 2].into_iter().collect()))
+            .collect()
+    }
+
+    fn run(strategy: RestartStrategy, generations: usize) -> (Vec<TestIndividual>, RunReport) {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        Runner::new()
+            .until(Termination::MaxGenerations(generations))
+            .run(&mut rng, &mut ga(strategy), population())
+    }
+
+    #[test]
+    fn partial_reseed_keeps_the_elites() {
+        let strategy = RestartStrategy::PartialReseed { elites: 1, range: (10.0, 20.0) };
+        let (population, report) = run(strategy.clone(), 4);
+
+        // Restarted once the best fitness stayed put for two generations
+        let restarts: Vec<&RestartEvent> = report.restarts().collect();
+        assert_eq!(restarts, [&RestartEvent { generation: 2, strategy, population: 4 }]);
+
+        assert_eq!(population[0].chromosome().iter().collect::<Vec<_>>(), [&3.0, &3.0]);
+        assert!(population[1..].iter().all(|individual| individual.fitness() >= 20.0));
+    }
+
+    #[test]
+    fn increase_population_grows_up_to_max() {
+        let strategy = RestartStrategy::IncreasePopulation {
+            factor: 2.0,
+            max: 12,
+            elites: 2,
+            range: (-1.0, 1.0),
+        };
+
+        let (population, report) = run(strategy, 13);
+
+        // Restarted every third generation
+        let sizes: Vec<usize> = report.restarts().map(|restart| restart.population).collect();
+        assert_eq!(sizes, [8, 12, 12, 12]);
+        assert_eq!(population.len(), 12);
+    }
+
+    #[test]
+    fn lineage_starts_over_after_a_restart() {
+        let strategy = RestartStrategy::IncreasePopulation {
+            factor: 2.0,
+            max: 8,
+            elites: 1,
+            range: (-1.0, 1.0),
+        };
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut ga = ga(strategy).with_observer(LineageRecorder::new());
+
+        let (population, report) = Runner::new()
+            .until(Termination::MaxGenerations(5))
+            .run(&mut rng, &mut ga, population());
+
+        assert_eq!(report.restarts().count(), 1);
+        assert_eq!(population.len(), 8);
+
+        // Restarted population was recorded without any parents
+        let store = ga.observer().store();
+        assert_eq!(store.current().len(), 8);
+
+        let founders: Vec<&LineageRecord> =
+            store.records().iter().filter(|record| record.parents.is_empty()).collect();
+        assert_eq!(founders.len(), 4 + 8);
+        assert!(founders[4..].iter().all(|record| record.generation == 3));
+    }
+
+    #[test]
+    fn hyper_mutation_bursts() {
+        let strategy = RestartStrategy::HyperMutation { chance: 1.0, coeff: 1.0, generations: 2 };
+        let (_, report) = run(strategy, 6);
+
+        let diversity: Vec<f32> = report.history.iter().map(|generation| generation.diversity).collect();
+
+        // Diversity only shrinks without mutation, until the burst kicks in
+        assert_eq!(report.restarts().count(), 1);
+        assert!(diversity[3] > diversity[2]);
+        assert_eq!(report.history[2].stats.restart.as_ref().unwrap().generation, 2);
+    }
+
+    #[test]
+    fn restarts_binary_genes() {
+        let strategies = [
+            RestartStrategy::PartialReseed { elites: 1, range: (0.0, 1.0) },
+            RestartStrategy::HyperMutation { chance: 1.0, coeff: 1.0, generations: 2 },
+        ];
+
+        for strategy in strategies {
+            let mut rng = StdRng::seed_from_u64(42);
+
+            let mut ga = GeneticAlgorithm::new(RankSelection, UniformCrossover, BitFlipMutation::new(0.0))
+                .with_restarts(Restarts::new(Termination::Stagnation(2), strategy));
+
+            let population: Vec<BitIndividual> = (0..4)
+                .map(|i| BitIndividual::create((0..8).map(|bit| bit < i).collect()))
+                .collect();
+
+            let (population, report) = Runner::new()
+                .until(Termination::MaxGenerations(8))
+                .run(&mut rng, &mut ga, population);
+
+            assert!(report.restarts().count() > 0);
+            assert_eq!(population.len(), 4);
+            assert!(report.history.iter().all(|generation| generation.diversity.is_finite()));
+        }
+    }
+}
+// ---------------------------------------------------------------
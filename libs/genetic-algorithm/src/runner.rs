@@ -0,0 +1,406 @@
+use crate::*;
+
+use std::time::{Duration, Instant};
+
+// ----------------------- Definitions ---------------------------
+// Drives generations until one of its termination conditions is met and
+// keeps the statistics of every generation on the way.
+//
+// `run` covers the plain "evaluate, evolve, repeat" loop; anything that
+// evaluates its population differently (e.g. the simulation) can feed the
+// runner through `record` instead.
+#[derive(Clone, Debug)]
+pub struct Runner {
+    conditions: Vec<Termination>,
+    started: Option<Instant>,
+    history: Vec<GenerationStatistics>,
+    stopped_by: Option<Termination>,
+}
+
+// Conditions are checked after every generation, the run stops as soon as
+// any of the runner's conditions is met
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Termination {
+    MaxGenerations(usize),
+    // Best fitness of a generation reached the target
+    FitnessTarget(f32),
+    // Best fitness hasn't improved for the given number of generations
+    Stagnation(usize),
+    // Mean distance of the chromosomes from their centroid dropped below
+    // the threshold
+    DiversityCollapse(f32),
+    TimeBudget(Duration),
+    // Met only once all of the conditions are met at the same time
+    All(Vec<Termination>),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenerationStatistics {
+    pub generation: usize,
+    pub stats: Statistics,
+    // NaN when it wasn't measured, see `Termination::uses_diversity`
+    #[serde(with = "non_finite")]
+    pub diversity: f32,
+    // Since the runner was started; zero unless a condition uses the clock
+    // (see `Termination::uses_clock`)
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct RunReport {
+    pub stopped_by: Termination,
+    pub generations: usize,
+    pub elapsed: Duration,
+    pub history: Vec<GenerationStatistics>,
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Runner Implementation -------------------
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Self {
+            conditions: Vec::new(),
+            started: None,
+            history: Vec::new(),
+            stopped_by: None,
+        }
+    }
+
+    pub fn until(mut self, condition: Termination) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn history(&self) -> &[GenerationStatistics] {
+        &self.history
+    }
+
+    pub fn stopped_by(&self) -> Option<&Termination> {
+        self.stopped_by.as_ref()
+    }
+
+    // Starts the clock (see `Termination::TimeBudget`); `run` does so on its
+    // own, otherwise it starts with the first `record`.
+    //
+    // The clock is left alone unless a condition uses it, since there's none
+    // on some targets (e.g. `wasm32-unknown-unknown`).
+    pub fn start(&mut self) {
+        if self.uses_clock() {
+            self.started = Some(Instant::now());
+        }
+    }
+
+    fn uses_clock(&self) -> bool {
+        self.conditions.iter().any(Termination::uses_clock)
+    }
+
+    // Records a generation that has just been evaluated, returns whether the
+    // run should stop
+    pub fn record<I, G>(&mut self, population: &[I], stats: Statistics) -> bool
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        assert!(!self.conditions.is_empty(), "runner has no termination conditions");
+
+        let elapsed = if self.uses_clock() {
+            self.started.get_or_insert_with(Instant::now).elapsed()
+        } else {
+            Duration::ZERO
+        };
+
+        self.history.push(GenerationStatistics {
+            generation: self.history.len(),
+            stats,
+            diversity: diversity(population),
+            elapsed,
+        });
+
+        self.stopped_by = self
+            .conditions
+            .iter()
+            .find(|condition| condition.is_met(&self.history, elapsed))
+            .cloned();
+
+        self.stopped_by.is_some()
+    }
+
+    // Evolves the population until a condition is met, returns the last
+    // evaluated population (the one the final statistics describe).
+    //
+    // Conditions are checked before every generation gets evolved, so the
+    // population that meets them never is; the statistics of the evolved
+    // ones are the optimizer's (e.g. with its mutation parameters).
+    pub fn run<O, I, G>(
+        mut self,
+        rng: &mut dyn RngCore,
+        optimizer: &mut O,
+        mut population: Vec<I>,
+    ) -> (Vec<I>, RunReport)
+    where
+        O: Optimizer<G>,
+        I: Individual<G>,
+        G: Gene,
+    {
+        self.start();
+
+        loop {
+            if self.record(&population, Statistics::new(&population)) {
+                return (population, self.into_report());
+            }
+
+            let (children, stats) = optimizer.evolve(rng, &population);
+
+            if let Some(last) = self.history.last_mut() {
+                last.stats = stats;
+            }
+
+            population = children;
+        }
+    }
+
+    pub fn into_report(self) -> RunReport {
+        RunReport {
+            stopped_by: self.stopped_by.expect("runner hasn't stopped yet"),
+            generations: self.history.len(),
+            elapsed: self.started.map_or(Duration::ZERO, |started| started.elapsed()),
+            history: self.history,
+        }
+    }
+}
+
+impl RunReport {
+    // Every restart the optimizer went through, see `Restarts`
+    pub fn restarts(&self) -> impl Iterator<Item = &RestartEvent> {
+        self.history
+            .iter()
+            .filter_map(|generation| generation.stats.restart.as_ref())
+    }
+}
+
+impl Termination {
+    // Whether `is_met` looks at the elapsed time at all
+    pub fn uses_clock(&self) -> bool {
+        match self {
+            Termination::TimeBudget(_) => true,
+            Termination::All(conditions) => conditions.iter().any(Termination::uses_clock),
+            _ => false,
+        }
+    }
+
+    // Whether `is_met` looks at the diversity at all
+    pub fn uses_diversity(&self) -> bool {
+        match self {
+            Termination::DiversityCollapse(_) => true,
+            Termination::All(conditions) => conditions.iter().any(Termination::uses_diversity),
+            _ => false,
+        }
+    }
+
+    pub fn is_met(&self, history: &[GenerationStatistics], elapsed: Duration) -> bool {
+        let Some(last) = history.last() else {
+            return false;
+        };
+
+        match self {
+            Termination::MaxGenerations(generations) => history.len() >= *generations,
+
+            Termination::FitnessTarget(target) => last.stats.max_fitness >= *target,
+
+            Termination::Stagnation(generations) => {
+                if history.len() <= *generations {
+                    return false;
+                }
+
+                let (before, recent) = history.split_at(history.len() - generations);
+                let best = |generations: &[GenerationStatistics]| {
+                    generations
+                        .iter()
+                        .map(|generation| generation.stats.max_fitness)
+                        .fold(f32::NEG_INFINITY, f32::max)
+                };
+
+                best(recent) <= best(before)
+            }
+
+            Termination::DiversityCollapse(threshold) => last.diversity < *threshold,
+
+            Termination::TimeBudget(budget) => elapsed >= *budget,
+
+            Termination::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.is_met(history, elapsed)),
+        }
+    }
+}
+
+// Mean Euclidean distance of the chromosomes from their centroid, NaN for
+// genes that aren't real numbers (see `RealGene`)
+pub(crate) fn diversity<I, G>(population: &[I]) -> f32
+where
+    I: Individual<G>,
+    G: Gene,
+{
+    let Some(first) = population.first() else {
+        return 0.0;
+    };
+
+    let Some(population) = population
+        .iter()
+        .map(|individual| individual.chromosome().iter().map(G::to_real).collect())
+        .collect::<Option<Vec<Chromosome>>>()
+    else {
+        return f32::NAN;
+    };
+
+    let mut centroid = vec![0.0; first.chromosome().len()];
+
+    for chromosome in &population {
+        for (sum, gene) in centroid.iter_mut().zip(chromosome.iter()) {
+            *sum += gene / population.len() as f32;
+        }
+    }
+
+    let centroid: Chromosome = centroid.into_iter().collect();
+
+    population
+        .iter()
+        .map(|chromosome| chromosome.distance(&centroid))
+        .sum::<f32>()
+        / population.len() as f32
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn history(max_fitness: &[f32]) -> Vec<GenerationStatistics> {
+        max_fitness
+            .iter()
+            .enumerate()
+            .map(|(generation, &fitness)| GenerationStatistics {
+                generation,
+                stats: Statistics::new(&[TestIndividual::new(fitness)]),
+                diversity: 1.0,
+                elapsed: Duration::ZERO,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn conditions() {
+        let history = history(&[1.0, 3.0, 2.0, 3.0]);
+        let elapsed = Duration::from_secs(5);
+
+        assert!(Termination::MaxGenerations(4).is_met(&history, elapsed));
+        assert!(!Termination::MaxGenerations(5).is_met(&history, elapsed));
+
+        assert!(Termination::FitnessTarget(3.0).is_met(&history, elapsed));
+        assert!(!Termination::FitnessTarget(3.5).is_met(&history, elapsed));
+
+        assert!(Termination::Stagnation(2).is_met(&history, elapsed));
+        assert!(!Termination::Stagnation(3).is_met(&history, elapsed));
+
+        assert!(Termination::DiversityCollapse(1.5).is_met(&history, elapsed));
+        assert!(Termination::TimeBudget(Duration::from_secs(5)).is_met(&history, elapsed));
+
+        assert!(!Termination::All(vec![
+            Termination::FitnessTarget(3.0),
+            Termination::MaxGenerations(10),
+        ])
+        .is_met(&history, elapsed));
+    }
+
+    #[test]
+    fn diversity_is_mean_distance_from_centroid() {
+        let population: Vec<TestIndividual> = [[0.0, 0.0], [2.0, 0.0], [1.0, 3.0], [1.0, -3.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+            .collect();
+
+        approx::assert_relative_eq!(diversity(&population), 2.0);
+    }
+
+    #[test]
+    fn runs_until_a_condition_is_met() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let population: Vec<TestIndividual> = (0..10)
+            .map(|_| TestIndividual::create(vec![0.0; 3].into_iter().collect()))
+            .collect();
+
+        let (population, report) = Runner::new()
+            .until(Termination::FitnessTarget(5.0))
+            .until(Termination::MaxGenerations(500))
+            .run(&mut rng, &mut ga, population);
+
+        assert_eq!(report.stopped_by, Termination::FitnessTarget(5.0));
+        assert_eq!(report.generations, report.history.len());
+        assert!(report.generations < 500);
+
+        // None of the conditions needs the clock
+        assert_eq!(report.elapsed, Duration::ZERO);
+
+        let best = population.iter().map(|i| i.fitness()).fold(f32::NEG_INFINITY, f32::max);
+        approx::assert_relative_eq!(best, report.history.last().unwrap().stats.max_fitness);
+    }
+
+    #[test]
+    fn does_not_evolve_past_the_last_generation() {
+        struct Counting(usize);
+
+        impl Optimizer for Counting {
+            fn evolve<I>(&mut self, _: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+            where
+                I: Individual,
+            {
+                self.0 += 1;
+                let children = population.iter().map(|i| I::create(i.chromosome().clone())).collect();
+                (children, Statistics::new(population))
+            }
+        }
+
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut optimizer = Counting(0);
+
+        let population = (0..2)
+            .map(|i| TestIndividual::create(vec![i as f32; 3].into_iter().collect()))
+            .collect::<Vec<_>>();
+
+        let (_, report) = Runner::new()
+            .until(Termination::MaxGenerations(1))
+            .run(&mut rng, &mut optimizer, population.clone());
+
+        assert_eq!(report.generations, 1);
+        assert_eq!(optimizer.0, 0);
+
+        let (_, report) = Runner::new()
+            .until(Termination::MaxGenerations(5))
+            .run(&mut rng, &mut optimizer, population);
+
+        assert_eq!(report.generations, 5);
+        assert_eq!(optimizer.0, 4);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,313 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Fitness-proportional (roulette wheel) selection. Fitness must not be
+// negative, so unless it's guaranteed to be, wrap it in `Scaled`. NaN and
+// infinite fitness (e.g. a death penalty) get no weight.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RouletteWheelSelection;
+
+// Selection method that only gets to see transformed fitness, e.g.
+// `Scaled::new(RouletteWheelSelection, FitnessTransform::Windowing)`.
+//
+// Only selection is affected, `Statistics` keep reporting the raw fitness.
+// Transforms can be chained by nesting, the outermost one is applied first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Scaled<S> {
+    selection: S,
+    transform: FitnessTransform,
+}
+
+// Every transform maps a population where everyone is equally fit to all
+// ones, so that selection falls back to picking uniformly. NaN and infinite
+// fitness is left out of the population's min, max and average and always
+// maps to zero.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FitnessTransform {
+    // Goldberg's linear scaling: keeps the average and maps the best to
+    // `multiple` times the average (usually 1.2 - 2.0), or stops at the worst
+    // ending up at zero. Works on windowed fitness, so negative fitness is fine.
+    Linear { multiple: f32 },
+    // f - (avg - c * sigma), anything below zero is cut to zero (c is usually 1 - 3)
+    SigmaTruncation { c: f32 },
+    // (f - min) ^ exponent
+    PowerLaw { exponent: f32 },
+    // f - min
+    Windowing,
+    // rank / n, from 1 / n for the worst to 1.0 for the best
+    Rank,
+}
+// ---------------------------------------------------------------
+
+
+// ----------------- Roulette Wheel Implementation ---------------
+impl SelectionMethod for RouletteWheelSelection {
+    fn select_index(&self, rng: &mut dyn RngCore, fitness: &[f32]) -> usize {
+        assert!(!fitness.is_empty(), "got an empty population");
+
+        assert!(
+            fitness.iter().filter(|fitness| fitness.is_finite()).all(|fitness| *fitness >= 0.0),
+            "roulette wheel selection got a negative fitness, see `Scaled`"
+        );
+
+        let indices: Vec<usize> = (0..fitness.len()).collect();
+        let weight = |index: &usize| if fitness[*index].is_finite() { fitness[*index] } else { 0.0 };
+
+        // Nobody has any weight, so everybody is equally (un)fit
+        match indices.choose_weighted(rng, weight) {
+            Ok(&index) => index,
+            Err(_) => rng.random_range(0..fitness.len()),
+        }
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------- Scaled Implementation -------------------
+impl<S> Scaled<S> {
+    pub fn new(selection: S, transform: FitnessTransform) -> Self {
+        match transform {
+            FitnessTransform::Linear { multiple } => assert!(multiple > 1.0),
+            FitnessTransform::SigmaTruncation { c } => assert!(c > 0.0),
+            FitnessTransform::PowerLaw { exponent } => assert!(exponent > 0.0),
+            FitnessTransform::Windowing | FitnessTransform::Rank => {}
+        }
+
+        Self { selection, transform }
+    }
+
+    pub fn transform(&self) -> FitnessTransform {
+        self.transform
+    }
+}
+
+impl<S> SelectionMethod for Scaled<S>
+where
+    S: SelectionMethod,
+{
+    fn select_index(&self, rng: &mut dyn RngCore, fitness: &[f32]) -> usize {
+        self.selection.select_index(rng, &self.transform.apply(fitness))
+    }
+}
+
+impl FitnessTransform {
+    pub fn apply(&self, fitness: &[f32]) -> Vec<f32> {
+        if fitness.is_empty() {
+            return Vec::new();
+        }
+
+        let finite: Vec<f32> = fitness.iter().cloned().filter(|fitness| fitness.is_finite()).collect();
+
+        let min = finite.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max = finite.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+
+        // Nobody (finite) is any better than anybody else
+        if finite.is_empty() || max - min <= f32::EPSILON * max.abs().max(1.0) {
+            return fitness
+                .iter()
+                .map(|fitness| if fitness.is_finite() || finite.is_empty() { 1.0 } else { 0.0 })
+                .collect();
+        }
+
+        let windowed = fitness.iter().map(|fitness| fitness - min);
+        let avg = finite.iter().sum::<f32>() / finite.len() as f32;
+
+        let transformed: Vec<f32> = match *self {
+            FitnessTransform::Linear { multiple } => {
+                let (avg, max) = (avg - min, max - min);
+
+                // Below that the worst would have to go negative, so the
+                // windowed fitness is as far as the scaling can go
+                let (a, b) = if max >= multiple * avg {
+                    let delta = max - avg;
+                    ((multiple - 1.0) * avg / delta, avg * (max - multiple * avg) / delta)
+                } else {
+                    (1.0, 0.0)
+                };
+
+                windowed.map(|fitness| (a * fitness + b).max(0.0)).collect()
+            }
+
+            FitnessTransform::SigmaTruncation { c } => {
+                let variance = finite
+                    .iter()
+                    .map(|fitness| (fitness - avg).powi(2))
+                    .sum::<f32>()
+                    / finite.len() as f32;
+
+                let floor = avg - c * variance.sqrt();
+
+                fitness.iter().map(|fitness| (fitness - floor).max(0.0)).collect()
+            }
+
+            FitnessTransform::PowerLaw { exponent } => {
+                windowed.map(|fitness| fitness.powf(exponent)).collect()
+            }
+
+            FitnessTransform::Windowing => windowed.collect(),
+
+            FitnessTransform::Rank => {
+                let mut order: Vec<usize> =
+                    (0..fitness.len()).filter(|&index| fitness[index].is_finite()).collect();
+
+                order.sort_by(|&a, &b| fitness[a].total_cmp(&fitness[b]));
+
+                let mut ranks = vec![0.0; fitness.len()];
+
+                for (rank, index) in order.into_iter().enumerate() {
+                    ranks[index] = (rank + 1) as f32 / finite.len() as f32;
+                }
+
+                ranks
+            }
+        };
+
+        transformed
+            .into_iter()
+            .zip(fitness)
+            .map(|(transformed, fitness)| if fitness.is_finite() { transformed } else { 0.0 })
+            .collect()
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn applied(transform: FitnessTransform) -> Vec<f32> {
+        transform.apply(&[-2.0, 0.0, 1.0, 5.0])
+    }
+
+    #[test]
+    fn transforms() {
+        approx::assert_relative_eq!(
+            applied(FitnessTransform::Windowing).as_slice(),
+            [0.0, 2.0, 3.0, 7.0].as_ref()
+        );
+
+        approx::assert_relative_eq!(
+            applied(FitnessTransform::Rank).as_slice(),
+            [0.25, 0.5, 0.75, 1.0].as_ref()
+        );
+
+        approx::assert_relative_eq!(
+            applied(FitnessTransform::PowerLaw { exponent: 2.0 }).as_slice(),
+            [0.0, 4.0, 9.0, 49.0].as_ref()
+        );
+
+        // avg = 2.0, sigma = 2.0
+        approx::assert_relative_eq!(
+            FitnessTransform::SigmaTruncation { c: 1.0 }.apply(&[0.0, 0.0, 4.0, 4.0]).as_slice(),
+            [0.0, 0.0, 4.0, 4.0].as_ref()
+        );
+
+        approx::assert_relative_eq!(
+            FitnessTransform::SigmaTruncation { c: 0.5 }.apply(&[0.0, 0.0, 4.0, 4.0]).as_slice(),
+            [0.0, 0.0, 3.0, 3.0].as_ref()
+        );
+
+        // Windowed avg = 3.0, best = 7.0 >= 2 * avg, so best becomes 6.0
+        let linear = applied(FitnessTransform::Linear { multiple: 2.0 });
+        approx::assert_relative_eq!(linear.as_slice(), [0.75, 2.25, 3.0, 6.0].as_ref());
+        approx::assert_relative_eq!(linear.iter().sum::<f32>() / 4.0, 3.0);
+
+        // Can't stretch that far without going negative, stays windowed
+        approx::assert_relative_eq!(
+            applied(FitnessTransform::Linear { multiple: 3.0 }).as_slice(),
+            [0.0, 2.0, 3.0, 7.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn equally_fit_populations_are_selected_uniformly() {
+        for transform in [
+            FitnessTransform::Linear { multiple: 2.0 },
+            FitnessTransform::SigmaTruncation { c: 2.0 },
+            FitnessTransform::PowerLaw { exponent: 1.5 },
+            FitnessTransform::Windowing,
+        ] {
+            assert_eq!(transform.apply(&[-3.0; 3]), [1.0; 3]);
+        }
+    }
+
+    #[test]
+    fn non_finite_fitness_gets_no_weight() {
+        for transform in [
+            FitnessTransform::Linear { multiple: 2.0 },
+            FitnessTransform::SigmaTruncation { c: 2.0 },
+            FitnessTransform::PowerLaw { exponent: 1.5 },
+            FitnessTransform::Windowing,
+            FitnessTransform::Rank,
+        ] {
+            let scaled = transform.apply(&[f32::NEG_INFINITY, -2.0, f32::NAN, 1.0, 5.0]);
+
+            assert!(scaled.iter().all(|fitness| fitness.is_finite() && *fitness >= 0.0));
+            assert_eq!([scaled[0], scaled[2]], [0.0, 0.0]);
+            assert!(scaled[4] > scaled[3]);
+        }
+
+        approx::assert_relative_eq!(
+            FitnessTransform::Windowing.apply(&[f32::NEG_INFINITY, -2.0, 1.0]).as_slice(),
+            [0.0, 0.0, 3.0].as_ref()
+        );
+
+        assert_eq!(FitnessTransform::Windowing.apply(&[f32::NAN, -3.0, -3.0]), [0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn roulette_survives_a_death_penalty() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            Scaled::new(RouletteWheelSelection, FitnessTransform::Windowing),
+            UniformCrossover,
+            GaussianMutation::new(0.0, 0.0),
+        );
+
+        let population: Vec<TestIndividual> = [f32::NEG_INFINITY, -3.0, -1.0]
+            .into_iter()
+            .map(|gene| TestIndividual::create(vec![gene].into_iter().collect()))
+            .collect();
+
+        let (children, _) = ga.evolve(&mut rng, &population);
+        assert!(children.iter().all(|child| child.fitness() == -1.0));
+
+        let picks: Vec<usize> = (0..100)
+            .map(|_| RouletteWheelSelection.select_index(&mut rng, &[f32::NAN, 0.0, 2.0]))
+            .collect();
+
+        assert!(picks.iter().all(|&pick| pick == 2));
+    }
+
+    #[test]
+    fn roulette_works_on_negative_fitness_once_scaled() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            Scaled::new(RouletteWheelSelection, FitnessTransform::Windowing),
+            UniformCrossover,
+            GaussianMutation::new(0.0, 0.0),
+        );
+
+        let population: Vec<TestIndividual> = [-4.0, -3.0, -1.0]
+            .into_iter()
+            .map(|gene| TestIndividual::create(vec![gene].into_iter().collect()))
+            .collect();
+
+        let (children, stats) = ga.evolve(&mut rng, &population);
+
+        // The worst one has no weight left, raw fitness is still reported
+        assert!(children.iter().all(|child| child.fitness() > -4.0));
+        approx::assert_relative_eq!(stats.min_fitness, -4.0);
+        approx::assert_relative_eq!(stats.avg_fitness, -8.0 / 3.0);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,280 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Niching layer: groups similar chromosomes into species, so that a single
+// strategy can't take over the whole population in a few generations.
+//
+// Every individual joins the first species whose representative (its first
+// member) is closer than `threshold`, otherwise it founds a new species.
+// Fitness is then shared inside each species (divided by its size) and every
+// species gets offspring proportional to its total shared fitness.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Speciation {
+    threshold: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpeciesStatistics {
+    pub size: usize,
+    pub offspring: usize,
+    #[serde(with = "non_finite")]
+    pub min_fitness: f32,
+    #[serde(with = "non_finite")]
+    pub max_fitness: f32,
+    #[serde(with = "non_finite")]
+    pub avg_fitness: f32,
+    #[serde(with = "non_finite")]
+    pub shared_fitness: f32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Species<G> {
+    pub(crate) members: Vec<SharedIndividual<G>>,
+    pub(crate) offspring: usize,
+}
+
+// Member of a species, carrying its shared fitness so that the regular
+// selection methods can be run on it
+#[derive(Debug)]
+pub(crate) struct SharedIndividual<G> {
+    // Position within the whole population
+    pub(crate) index: usize,
+    chromosome: Chromosome<G>,
+    raw_fitness: f32,
+    shared_fitness: f32,
+}
+// ---------------------------------------------------------------
+
+
+// ------------------ Speciation Implementation ------------------
+impl Speciation {
+    pub fn new(threshold: f32) -> Self {
+        assert!(threshold > 0.0);
+        Self { threshold }
+    }
+
+    pub fn threshold(&self) -> f32 {
+        self.threshold
+    }
+
+    pub(crate) fn speciate<I, G>(&self, population: &[I]) -> Vec<Species<G>>
+    where
+        I: Individual<G>,
+        G: Gene,
+    {
+        assert!(!population.is_empty());
+
+        // Step 1: Cluster individuals by distance to each species' representative
+        let mut clusters: Vec<Vec<(usize, &I)>> = Vec::new();
+
+        for (index, individual) in population.iter().enumerate() {
+            let cluster = clusters.iter_mut().find(|cluster| {
+                cluster[0].1.chromosome().distance(individual.chromosome()) < self.threshold
+            });
+
+            match cluster {
+                Some(cluster) => cluster.push((index, individual)),
+                None => clusters.push(vec![(index, individual)]),
+            }
+        }
+
+        // Step 2: Share fitness inside each species
+        // Fitness is shifted so that the worst individual sits at zero,
+        // otherwise negative fitness would break the proportional split
+        let min_fitness = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .fold(f32::INFINITY, f32::min);
+
+        let mut species: Vec<Species<G>> = clusters
+            .into_iter()
+            .map(|cluster| {
+                let size = cluster.len() as f32;
+
+                let members = cluster
+                    .into_iter()
+                    .map(|(index, individual)| SharedIndividual {
+                        index,
+                        chromosome: individual.chromosome().clone(),
+                        raw_fitness: individual.fitness(),
+                        shared_fitness: (individual.fitness() - min_fitness) / size,
+                    })
+                    .collect();
+
+                Species { members, offspring: 0 }
+            })
+            .collect();
+
+        // Step 3: Split the offspring between species
+        let weights: Vec<f32> = species.iter().map(Species::shared_fitness).collect();
+
+        let weights = if weights.iter().sum::<f32>() > 0.0 {
+            weights
+        } else {
+            // Nobody is better than anybody else, so fall back to species size
+            species.iter().map(|s| s.members.len() as f32).collect()
+        };
+
+        for (s, offspring) in species.iter_mut().zip(allocate(&weights, population.len())) {
+            s.offspring = offspring;
+        }
+
+        species
+    }
+}
+
+// Splits `total` proportionally to `weights` using the largest remainder
+// method, so the result always sums up to exactly `total`
+fn allocate(weights: &[f32], total: usize) -> Vec<usize> {
+    let sum: f32 = weights.iter().sum();
+
+    let quotas: Vec<f32> = weights
+        .iter()
+        .map(|weight| weight / sum * total as f32)
+        .collect();
+
+    let mut counts: Vec<usize> = quotas.iter().map(|quota| quota.floor() as usize).collect();
+    let assigned: usize = counts.iter().sum();
+
+    let mut by_remainder: Vec<usize> = (0..quotas.len()).collect();
+    by_remainder.sort_by(|&a, &b| {
+        (quotas[b] - quotas[b].floor())
+            .partial_cmp(&(quotas[a] - quotas[a].floor()))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    for &index in by_remainder.iter().cycle().take(total.saturating_sub(assigned)) {
+        counts[index] += 1;
+    }
+
+    counts
+}
+// ---------------------------------------------------------------
+
+
+// -------------------- Species Implementation -------------------
+impl<G> Species<G> {
+    fn shared_fitness(&self) -> f32 {
+        self.members.iter().map(|member| member.shared_fitness).sum()
+    }
+}
+
+impl SpeciesStatistics {
+    pub(crate) fn new<G>(species: &Species<G>) -> Self {
+        let fitnesses = species.members.iter().map(|member| member.raw_fitness);
+
+        Self {
+            size: species.members.len(),
+            offspring: species.offspring,
+            min_fitness: fitnesses.clone().fold(f32::INFINITY, f32::min),
+            max_fitness: fitnesses.clone().fold(f32::NEG_INFINITY, f32::max),
+            avg_fitness: fitnesses.sum::<f32>() / species.members.len() as f32,
+            shared_fitness: species.shared_fitness(),
+        }
+    }
+}
+
+impl<G> Individual<G> for SharedIndividual<G> {
+    fn create(chromosome: Chromosome<G>) -> Self {
+        Self {
+            index: 0,
+            chromosome,
+            raw_fitness: 0.0,
+            shared_fitness: 0.0,
+        }
+    }
+
+    fn chromosome(&self) -> &Chromosome<G> {
+        &self.chromosome
+    }
+
+    // Selection inside a species works on the shared fitness
+    fn fitness(&self) -> f32 {
+        self.shared_fitness
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn individual(genes: &[f32]) -> TestIndividual {
+        TestIndividual::create(genes.iter().cloned().collect())
+    }
+
+    #[test]
+    fn clusters_by_distance() {
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[10.0, 10.0]),
+            individual(&[0.5, 0.0]),
+            individual(&[10.0, 10.5]),
+            individual(&[10.5, 10.0]),
+        ];
+
+        let species = Speciation::new(1.0).speciate(&population);
+        let sizes: Vec<_> = species.iter().map(|s| s.members.len()).collect();
+
+        assert_eq!(sizes, vec![2, 3]);
+    }
+
+    #[test]
+    fn allocates_offspring_by_shared_fitness() {
+        // Fitness is the sum of genes, so after shifting by the minimum (0.0):
+        // species A = [0.0, 1.0] / 2 => 0.5
+        // species B = [20.0, 21.0, 21.0] / 3 => ~20.67
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[10.0, 10.0]),
+            individual(&[0.5, 0.5]),
+            individual(&[10.0, 11.0]),
+            individual(&[11.0, 10.0]),
+        ];
+
+        let species = Speciation::new(2.0).speciate(&population);
+        let offspring: Vec<_> = species.iter().map(|s| s.offspring).collect();
+
+        assert_eq!(offspring, vec![0, 5]);
+    }
+
+    #[test]
+    fn allocation_sums_up_to_total() {
+        assert_eq!(allocate(&[1.0, 1.0, 1.0], 10), vec![4, 3, 3]);
+        assert_eq!(allocate(&[0.2, 0.3, 0.5], 7), vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn evolve_reports_species() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        )
+        .with_speciation(Speciation::new(1.0));
+
+        let population = vec![
+            individual(&[0.0, 0.0]),
+            individual(&[1.0, 1.0]),
+            individual(&[5.0, 5.0]),
+            individual(&[5.0, 5.5]),
+        ];
+
+        let (new_population, stats) = ga.evolve(&mut rng, &population);
+
+        assert_eq!(new_population.len(), population.len());
+        assert_eq!(stats.species.len(), 3);
+        assert_eq!(stats.species.iter().map(|s| s.size).sum::<usize>(), 4);
+        assert_eq!(stats.species.iter().map(|s| s.offspring).sum::<usize>(), 4);
+    }
+}
+// ---------------------------------------------------------------
@@ -0,0 +1,186 @@
+use crate::*;
+
+// ----------------------- Definitions ---------------------------
+// Who dies to make room for a new child in the steady-state mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplacementPolicy {
+    // Always the worst individual in the population
+    Worst,
+    // The worst of `size` individuals picked at random, which gives the
+    // weaker ones a chance to survive for a bit longer
+    InverseTournament { size: usize },
+}
+// ---------------------------------------------------------------
+
+
+// ------------------ Steady-State Implementation ----------------
+impl ReplacementPolicy {
+    pub(crate) fn victim<I, G>(&self, rng: &mut dyn RngCore, population: &[I]) -> usize
+    where
+        I: Individual<G>,
+    {
+        let worst = |a: &usize, b: &usize| {
+            population[*a]
+                .fitness()
+                .partial_cmp(&population[*b].fitness())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+
+        match *self {
+            Self::Worst => (0..population.len()).min_by(worst),
+
+            Self::InverseTournament { size } => {
+                assert!(size > 0);
+
+                rand::seq::index::sample(rng, population.len(), size.min(population.len()))
+                    .into_iter()
+                    .min_by(worst)
+            }
+        }
+        .expect("got an empty population")
+    }
+}
+
+impl<S, C, M, Ob> GeneticAlgorithm<S, C, M, Ob>
+where
+    S: SelectionMethod,
+{
+    // Steady-state counterpart of `evolve`: breeds a single child from the
+    // whole population (species are ignored) and returns it together with
+    // the index of the individual it should replace.
+    //
+    // Every `population.len()` births make a round, before which the mutation
+    // method adapts and the operators get credited and picked, same as before
+    // a generation of `evolve`. Duplicate filter and local search see every
+    // child on its own; restarts and Baldwinian local search need whole
+    // generations, so they aren't supported.
+    pub fn evolve_one<I, G>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        replacement: ReplacementPolicy,
+    ) -> (usize, I)
+    where
+        I: Individual<G>,
+        G: Gene,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+        Ob: Observer<G>,
+    {
+        assert!(!population.is_empty());
+        assert!(self.restarts.is_none(), "restarts don't work in the steady-state mode");
+        assert!(
+            self.local_search
+                .as_ref()
+                .is_none_or(|local_search| local_search.learning() == Learning::Lamarckian),
+            "Baldwinian local search doesn't work in the steady-state mode"
+        );
+
+        if self.births == 0 {
+            self.prepare(rng, population);
+            self.adaptation.round(population.len());
+        }
+
+        // The victim's slot is the one the operators were picked for
+        let victim = replacement.victim(rng, population);
+        let (chromosome, parents) = self.breed(rng, population, victim, |parent| parent);
+        let mut child = [I::create(chromosome)];
+        let mut bred = true;
+
+        if let Some(duplicates) = &self.duplicates {
+            bred &= duplicates.filter(rng, &self.mutation_method, population, &mut child).is_empty();
+        }
+
+        if let Some(local_search) = &mut self.local_search {
+            bred &= local_search.apply(rng, &mut child).1.is_empty();
+        }
+
+        self.adaptation.born(population, victim, bred.then_some(parents));
+        self.births += 1;
+
+        if self.births >= population.len() {
+            self.births = 0;
+
+            if let Some(operator_selection) = &mut self.operator_selection {
+                operator_selection.replaced(&self.adaptation.unborn());
+            }
+        }
+
+        let [child] = child;
+        (victim, child)
+    }
+}
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::TestIndividual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn population() -> Vec<TestIndividual> {
+        [[1.0, 1.0], [0.0, 0.0], [3.0, 3.0], [2.0, 2.0]]
+            .iter()
+            .map(|genes| TestIndividual::create(genes.iter().cloned().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn worst_replaces_the_lowest_fitness() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let (victim, child) = ga.evolve_one(&mut rng, &population(), ReplacementPolicy::Worst);
+
+        assert_eq!(victim, 1);
+        assert_eq!(child.chromosome().len(), 2);
+    }
+
+    #[test]
+    fn mutation_adapts_every_round() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let mut ga = GeneticAlgorithm::new(
+            RankSelection,
+            UniformCrossover,
+            AdaptiveGaussianMutation::new(
+                0.5,
+                1.0,
+                MutationControl::Decay { rate: 0.5, min: MutationParameters { chance: 0.0, coeff: 0.0 } },
+            ),
+        );
+
+        let mut population = population();
+
+        // Rounds of four births start with the 1st, 5th and 9th one
+        for _ in 0..9 {
+            let (victim, child) = ga.evolve_one(&mut rng, &population, ReplacementPolicy::Worst);
+            population[victim] = child;
+        }
+
+        let coeff = MutationMethod::<f32>::parameters(&ga.mutation_method).unwrap().coeff;
+        approx::assert_relative_eq!(coeff, 0.25);
+    }
+
+    #[test]
+    fn inverse_tournament_never_picks_the_best() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let population = population();
+        let policy = ReplacementPolicy::InverseTournament { size: 2 };
+
+        for _ in 0..100 {
+            assert_ne!(policy.victim(&mut rng, &population), 2);
+        }
+    }
+}
+// ---------------------------------------------------------------
@@ -75,6 +75,12 @@ impl From<&sim::Input> for Input {
 
 
 // ---------------- Simulation Implementation  -------------------
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[wasm_bindgen]
 impl Simulation {
     #[wasm_bindgen(constructor)]
@@ -85,6 +91,20 @@ impl Simulation {
         Self { rng, sim }
     }
 
+    // e.g. `Simulation.with_operators("rank", "uniform", "gaussian(chance = 0.01, coeff = 0.3)")`
+    pub fn with_operators(selection: &str, crossover: &str, mutation: &str) -> Result<Simulation, JsError> {
+        let mut rng = rand::rng();
+        let sim = sim::Simulation::with_operators(&mut rng, selection, crossover, mutation)?;
+
+        Ok(Self { rng, sim })
+    }
+
+    pub fn set_operators(&mut self, selection: &str, crossover: &str, mutation: &str) -> Result<(), JsError> {
+        self.sim.set_operators(selection, crossover, mutation)?;
+
+        Ok(())
+    }
+
     pub fn world(&self) -> World {
         World::from(self.sim.world())
     }
@@ -96,12 +116,18 @@ impl Simulation {
     pub fn complete_one_evolution(&mut self) -> String {
         let stats = self.sim.train(&mut self.rng);
 
-        format!(
+        let mut summary = format!(
             "min={:.2}, max={:.2}, avg={:.2}",
             stats.min_fitness,
             stats.max_fitness,
             stats.avg_fitness,
-        )
+        );
+
+        if let Some(mutation) = stats.mutation {
+            summary += &format!(", chance={:.3}, coeff={:.3}", mutation.chance, mutation.coeff);
+        }
+
+        summary
     }
 }
 // ---------------------------------------------------------------
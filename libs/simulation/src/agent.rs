@@ -8,7 +8,9 @@ pub struct Agent {
     pub(crate) rotation: na::Rotation2<f32>,
     pub(crate) eye: Eye,
     pub(crate) brain: Brain,
-    pub(crate) satiation: usize
+    pub(crate) satiation: usize,
+    // Steps lived so far
+    pub(crate) age: usize,
 }
 
 #[derive(Debug)]
@@ -29,6 +31,7 @@ impl Agent {
             eye,
             brain,
             satiation: 0,
+            age: 0,
         }
     }
 
@@ -89,8 +92,53 @@ impl AgentIndividual {
         }
     }
 
+    // Food eaten per `GENERATION_LENGTH` steps, so that agents of different
+    // ages can be compared against each other in the steady-state mode.
+    //
+    // Every agent counts as `AGE_SMOOTHING` steps older than it is, so that a
+    // newborn that happened to bump into food right away doesn't outrank the
+    // agents that have been eating steadily for a whole generation.
+    pub fn from_agent_by_age(agent: &Agent) -> Self {
+        let age = agent.age + AGE_SMOOTHING;
+
+        Self {
+            fitness: agent.satiation as f32 * GENERATION_LENGTH as f32 / age as f32,
+            chromosome: agent.as_chromosome(),
+        }
+    }
+
     pub fn into_agent(self, rng: &mut dyn RngCore) -> Agent {
         Agent::from_chromosome(self.chromosome, rng)
     }
 }
 // ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ga::Individual;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    fn agent(rng: &mut dyn RngCore, satiation: usize, age: usize) -> Agent {
+        let mut agent = Agent::random(rng);
+        agent.satiation = satiation;
+        agent.age = age;
+        agent
+    }
+
+    #[test]
+    fn lucky_newborns_do_not_outrank_steady_eaters() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let newborn = AgentIndividual::from_agent_by_age(&agent(&mut rng, 1, 10));
+        let veteran = AgentIndividual::from_agent_by_age(&agent(&mut rng, 5, GENERATION_LENGTH));
+
+        assert!(veteran.fitness() > newborn.fitness());
+    }
+}
+// ---------------------------------------------------------------
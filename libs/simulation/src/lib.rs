@@ -3,7 +3,10 @@ use rand::{
     Rng, 
     RngCore
 };
-use std::f32::consts::FRAC_PI_2;
+use std::{
+    f32::consts::FRAC_PI_2,
+    path::Path,
+};
 
 use lib_neural_network as nn;
 use lib_genetic_algorithm as ga;
@@ -28,15 +31,26 @@ const SPEED_MAX: f32 = 0.005;
 const SPEED_ACCEL: f32 = 0.2;
 const ROTATION_ACCEL: f32 = FRAC_PI_2;
 const GENERATION_LENGTH: usize = 2500;
+const HALL_OF_FAME_SIZE: usize = 10;
+// Steps added to the age of every agent when its food is normalized by age,
+// see `AgentIndividual::from_agent_by_age`
+const AGE_SMOOTHING: usize = GENERATION_LENGTH / 4;
 // ---------------------------------------------------------------
 
 
 // ----------------------- Definitions ---------------------------
+// Any `ga::Optimizer` can drive the evolution, the GA is just the default -
+// its operators are picked at runtime, see `Simulation::with_operators`
 #[derive(Debug)]
-pub struct Simulation {
+pub struct Simulation<O = ga::DynGeneticAlgorithm> {
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RankSelection, ga::UniformCrossover, ga::GaussianMutation>,
+    optimizer: O,
     age: usize,
+    generation: usize,
+    hall_of_fame: ga::HallOfFame,
+    // When set, agents are born and die one at a time instead of the whole
+    // population being swapped every `GENERATION_LENGTH` steps
+    steady_state: Option<ga::ReplacementPolicy>,
 }
 // ---------------------------------------------------------------
 
@@ -44,13 +58,10 @@ pub struct Simulation {
 // ---------------- Simulation Implementation  -------------------
 impl Simulation {
     pub fn random(rng: &mut dyn RngCore) -> Self {
-
-        let world = World::random(rng);
-
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RankSelection,
-            ga::UniformCrossover,
-            ga::GaussianMutation::new(0.01, 0.3),
+        let ga: ga::DynGeneticAlgorithm = ga::GeneticAlgorithm::new(
+            Box::new(ga::RankSelection),
+            Box::new(ga::UniformCrossover),
+            Box::new(ga::GaussianMutation::new(0.01, 0.3)),
             // ---------------------- ^--^ -^-^
             // | Chosen with a bit of experimentation.
             // |
@@ -63,13 +74,159 @@ impl Simulation {
             // ---
         );
 
-        Self { world, ga, age: 0 }
+        Self::with_optimizer(rng, ga)
+    }
+
+    // Operators are looked up by name in `ga::Registry::default()`, e.g.
+    // `with_operators(rng, "rank", "uniform", "gaussian(chance = 0.01, coeff = 0.3)")`
+    pub fn with_operators(
+        rng: &mut dyn RngCore,
+        selection: &str,
+        crossover: &str,
+        mutation: &str,
+    ) -> Result<Self, ga::RegistryError> {
+        let ga = ga::Registry::default().genetic_algorithm(selection, crossover, mutation)?;
+
+        Ok(Self::with_optimizer(rng, ga))
+    }
+
+    // Swaps the operators without touching the world, takes effect from the
+    // next generation on (everything else the optimizer was set up with, e.g.
+    // restarts, is kept)
+    pub fn set_operators(
+        &mut self,
+        selection: &str,
+        crossover: &str,
+        mutation: &str,
+    ) -> Result<(), ga::RegistryError> {
+        self.optimizer = ga::Registry::default()
+            .genetic_algorithm(selection, crossover, mutation)?
+            .with_options_of(&self.optimizer);
+
+        Ok(())
+    }
+
+    // Same as `set_operators`, but every child is bred with one of `crossovers`
+    // and one of `mutations`, picked by how well their children have done
+    // so far (see `ga::OperatorSelection`)
+    pub fn set_operator_portfolio(
+        &mut self,
+        selection: &str,
+        crossovers: &[&str],
+        mutations: &[&str],
+        credit: ga::CreditAssignment,
+    ) -> Result<(), ga::RegistryError> {
+        let registry = ga::Registry::default();
+
+        self.optimizer = ga::GeneticAlgorithm::new(
+            registry.selection(selection)?,
+            registry.crossover_portfolio(crossovers)?,
+            registry.mutation_portfolio(mutations)?,
+        )
+        .with_options_of(&self.optimizer)
+        .with_operator_selection(ga::OperatorSelection::new(credit));
+
+        Ok(())
+    }
+
+    // Restarts the evolution on its own once it gets stuck, instead of having
+    // to start over with a fresh `Simulation::random`; not in the steady-state
+    // mode, which never evolves a whole generation
+    pub fn with_restarts(mut self, restarts: ga::Restarts) -> Self {
+        assert!(self.steady_state.is_none(), "restarts don't work in the steady-state mode");
+
+        self.optimizer = self.optimizer.with_restarts(restarts);
+        self
+    }
+}
+
+impl<O> Simulation<O>
+where
+    O: ga::Optimizer,
+{
+    pub fn with_optimizer(rng: &mut dyn RngCore, optimizer: O) -> Self {
+        let world = World::random(rng);
+
+        Self {
+            world,
+            optimizer,
+            age: 0,
+            generation: 0,
+            hall_of_fame: ga::HallOfFame::new(HALL_OF_FAME_SIZE),
+            steady_state: None,
+        }
+    }
+
+    // Replaces one agent at a time instead of the whole generation, see
+    // `GeneticAlgorithm::evolve_one` for what the optimizer supports then
+    pub fn with_steady_state(mut self, replacement: ga::ReplacementPolicy) -> Self {
+        self.steady_state = Some(replacement);
+        self
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    // Best brains seen across all generations so far
+    pub fn hall_of_fame(&self) -> &ga::HallOfFame {
+        &self.hall_of_fame
+    }
+
+    // Gives every agent a new brain from `initializer`, e.g. to warm-start
+    // from the population (or the champion) of an earlier experiment; a world
+    // without agents is left as it is
+    pub fn initialize(
+        &mut self,
+        rng: &mut dyn RngCore,
+        initializer: &ga::Initializer,
+    ) -> Result<(), ga::CheckpointError> {
+        let Some(agent) = self.world.agents.first() else {
+            return Ok(());
+        };
+
+        let size = self.world.agents.len();
+        let genes = agent.as_chromosome().len();
+
+        self.world.agents = initializer
+            .chromosomes(rng, size, genes)?
+            .into_iter()
+            .map(|chromosome| Agent::from_chromosome(chromosome, rng))
+            .collect();
+
+        Ok(())
+    }
+
+    // Saves the brains of the current agents, see `ga::Initializer::load`
+    pub fn save_population(&self, path: impl AsRef<Path>) -> Result<(), ga::CheckpointError> {
+        let chromosomes: Vec<_> = self.world.agents.iter().map(Agent::as_chromosome).collect();
+        ga::Initializer::save(path, &chromosomes)
+    }
+
+    // Saves the best brain seen so far, there's none until the first
+    // generation is over
+    pub fn save_champion(&self, path: impl AsRef<Path>) -> Result<(), ga::CheckpointError> {
+        match self.hall_of_fame.best() {
+            Some(champion) => ga::Initializer::save(path, std::slice::from_ref(&champion.chromosome)),
+            None => Err(ga::CheckpointError::EmptyPopulation),
+        }
+    }
+
+    // Brings back up to `count` members of the hall of fame as newborn agents,
+    // replacing the agents that have eaten the least so far
+    pub fn inject_hall_of_fame(&mut self, rng: &mut dyn RngCore, count: usize) {
+        let mut hungriest: Vec<usize> = (0..self.world.agents.len()).collect();
+        hungriest.sort_by_key(|&index| self.world.agents[index].satiation);
+
+        for (index, member) in hungriest.into_iter().zip(self.hall_of_fame.members()).take(count) {
+            self.world.agents[index] = Agent::from_chromosome(member.chromosome.clone(), rng);
+        }
+    }
+
     pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
         self.process_brains();
         self.process_collisions(rng);
@@ -77,6 +234,20 @@ impl Simulation {
 
         self.age += 1;
 
+        for agent in &mut self.world.agents {
+            agent.age += 1;
+        }
+
+        if let Some(replacement) = self.steady_state {
+            // Spread births so that roughly the whole population is
+            // replaced over a single generation
+            let interval = (GENERATION_LENGTH / self.world.agents.len()).max(1);
+
+            if self.age.is_multiple_of(interval) {
+                self.replace_one(rng, replacement);
+            }
+        }
+
         if self.age > GENERATION_LENGTH {
             Some(self.evolve(rng))
         } else {
@@ -92,6 +263,29 @@ impl Simulation {
         }
     }
 
+    // Trains generation after generation until one of the runner's
+    // conditions is met
+    pub fn run(&mut self, rng: &mut dyn RngCore, mut runner: ga::Runner) -> ga::RunReport {
+        runner.start();
+
+        loop {
+            // Brains are fixed for the whole generation (apart from
+            // steady-state births), so diversity is measured up front
+            let population: Vec<_> = self
+                .world
+                .agents
+                .iter()
+                .map(AgentIndividual::from_agent)
+                .collect();
+
+            let stats = self.train(rng);
+
+            if runner.record(&population, stats) {
+                return runner.into_report();
+            }
+        }
+    }
+
     fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
         self.age = 0;
 
@@ -100,11 +294,24 @@ impl Simulation {
             .world
             .agents
             .iter()
-            .map(AgentIndividual::from_agent)
+            .map(if self.steady_state.is_some() {
+                AgentIndividual::from_agent_by_age
+            } else {
+                AgentIndividual::from_agent
+            })
             .collect();
 
+        self.hall_of_fame.update(&current_population, self.generation);
+        self.generation += 1;
+
+        // Steady-state agents are replaced continuously, so there's nothing
+        // to swap here - only report how the population is doing
+        if self.steady_state.is_some() {
+            return ga::Statistics::new(&current_population);
+        }
+
         // Step 2: Evolve agents
-        let (evolved_population, stats) = self.ga.evolve(rng, &current_population);
+        let (evolved_population, stats) = self.optimizer.evolve(rng, &current_population);
 
 
         // Step 3: Bring agents back from the genetic algorithm
@@ -116,6 +323,19 @@ impl Simulation {
         stats
     }
 
+    fn replace_one(&mut self, rng: &mut dyn RngCore, replacement: ga::ReplacementPolicy) {
+        let current_population: Vec<_> = self
+            .world
+            .agents
+            .iter()
+            .map(AgentIndividual::from_agent_by_age)
+            .collect();
+
+        let (victim, child) = self.optimizer.evolve_one(rng, &current_population, replacement);
+
+        self.world.agents[victim] = child.into_agent(rng);
+    }
+
     // Movement of the agents
     // Just add their randomly generated velocity to themselves till they reach max velocity...
     // Velocity control can be done later...
@@ -164,4 +384,64 @@ impl Simulation {
         }
     }
 }
+// ---------------------------------------------------------------
+
+
+// --------------------------- Tests -----------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{
+        SeedableRng,
+        rngs::StdRng
+    };
+
+    #[test]
+    fn swapping_operators_keeps_the_other_settings() {
+        let mut rng = StdRng::seed_from_u64(42);
+
+        let optimizer = ga::Registry::default()
+            .genetic_algorithm("rank", "uniform", "gaussian(chance = 0.01, coeff = 0.3)")
+            .unwrap()
+            .with_duplicate_filter(ga::DuplicateFilter::new(0.0, ga::DuplicateAction::Replace))
+            .with_restarts(ga::Restarts::new(
+                ga::Termination::Stagnation(50),
+                ga::RestartStrategy::PartialReseed { elites: 1, range: (-1.0, 1.0) },
+            ))
+            .with_local_search(ga::LocalSearch::new(
+                ga::LocalSearchMethod::HillClimbing { step: 0.1 },
+                5,
+                0.1,
+                ga::Learning::Lamarckian,
+            ))
+            .with_operator_selection(ga::OperatorSelection::new(
+                ga::CreditAssignment::ProbabilityMatching { adaptation: 0.5, min_probability: 0.1 },
+            ));
+
+        let mut simulation = Simulation::with_optimizer(&mut rng, optimizer);
+
+        let kept = |simulation: &Simulation| {
+            simulation.optimizer.duplicate_filter().is_some()
+                && simulation.optimizer.restarts().is_some()
+                && simulation.optimizer.local_search().is_some()
+                && simulation.optimizer.operator_selection().is_some()
+        };
+
+        simulation.set_operators("roulette", "uniform", "swap(chance = 0.1)").unwrap();
+        assert!(kept(&simulation));
+
+        let credit = ga::CreditAssignment::AdaptivePursuit {
+            adaptation: 0.5,
+            learning: 0.5,
+            min_probability: 0.1,
+        };
+
+        simulation
+            .set_operator_portfolio("rank", &["uniform"], &["swap(chance = 0.1)", "inversion(chance = 0.1)"], credit)
+            .unwrap();
+
+        assert!(kept(&simulation));
+        assert_eq!(simulation.optimizer.operator_selection().unwrap().credit_assignment(), credit);
+    }
+}
 // ---------------------------------------------------------------
\ No newline at end of file